@@ -0,0 +1,15 @@
+// benches/signing_bench.rs
+// Signing (witness generation) benchmarks for TopoShield
+// generate_path absorbs the seed once per path, so each Witness::new performs
+// 2 seed absorptions + 40 counter absorptions instead of 40 full H(seed || i) sponges.
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use toposhield::witness::Witness;
+
+fn bench_witness_new(c: &mut Criterion) {
+    c.bench_function("witness_new", |b| {
+        b.iter(|| Witness::new(black_box(b"Benchmark message"), black_box(b"bench_seed")))
+    });
+}
+
+criterion_group!(benches, bench_witness_new);
+criterion_main!(benches);
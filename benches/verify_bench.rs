@@ -0,0 +1,66 @@
+// benches/verify_bench.rs
+// Verification benchmarks for TopoShield
+// Params and the verifying key are loaded once by TopoShieldProver::new; each verify
+// only builds an AccumulatorStrategy (a handle on the cached params) and a transcript
+// reader over the proof bytes, then runs the SHPLONK check and final pairing.
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use toposhield::{
+    prover::{default_batch_chunk_size, TopoShieldProver, VerifyRequest},
+    witness::Witness,
+};
+
+fn bench_verify(c: &mut Criterion) {
+    let prover = TopoShieldProver::new().expect("prover setup");
+    let witness = Witness::new(b"Benchmark message", b"bench_seed");
+    let proof = prover.prove(witness.clone()).expect("proof generation");
+
+    c.bench_function("verify", |b| {
+        b.iter(|| {
+            prover
+                .verify(
+                    black_box(&proof),
+                    witness.h_pub,
+                    witness.h_sig,
+                    witness.desc_m,
+                    witness.m_hash,
+                )
+                .expect("verification")
+        })
+    });
+}
+
+/// Sweep proofs-per-task for 100 independent verifications
+fn bench_verify_many(c: &mut Criterion) {
+    let prover = TopoShieldProver::new().expect("prover setup");
+    let witness = Witness::new(b"Benchmark message", b"bench_seed");
+    let proof = prover.prove(witness.clone()).expect("proof generation");
+    let requests = vec![
+        VerifyRequest {
+            proof: &proof,
+            h_pub: witness.h_pub,
+            h_sig: witness.h_sig,
+            desc_m: witness.desc_m,
+            m_hash: witness.m_hash,
+        };
+        100
+    ];
+
+    let mut group = c.benchmark_group("verify_many");
+    group.sample_size(10);
+    let default = default_batch_chunk_size(requests.len());
+    let mut sizes = vec![1, 2, 4, 8, 16, 32, 100];
+    if !sizes.contains(&default) {
+        sizes.push(default);
+    }
+    let mut prover = prover;
+    for chunk_size in sizes {
+        prover = prover.with_batch_chunk_size(chunk_size);
+        group.bench_with_input(BenchmarkId::from_parameter(chunk_size), &chunk_size, |b, _| {
+            b.iter(|| prover.verify_many(black_box(&requests)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_verify, bench_verify_many);
+criterion_main!(benches);
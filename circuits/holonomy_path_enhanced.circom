@@ -1,10 +1,11 @@
 // circuits/holonomy_path_enhanced.circom
 // Enhanced TopoShield ZKP with structural validation
-// Genus = 5, path length = 20, faithful SL(2, Fp) representation
+// Genus = 5, path length = 20, integer SL(2, Fp) representation (src/manifold.rs)
 // All matrices have det = 1 and satisfy ∏[A_i, B_i] = I
 // CORRECTED: Processes path in REVERSE order to match mathematical holonomy definition
 
-include "./poseidon.circom";
+include "circomlib/circuits/poseidon.circom";
+include "circomlib/circuits/comparators.circom";
 
 // Matrix multiplication in SL(2, Fp)
 template SL2Multiply() {
@@ -18,29 +19,29 @@ template SL2Multiply() {
 }
 
 // Hardcoded generator matrices (0–19)
-// 0–4: a1–a5, 5–9: b1–b5, 10–14: a1⁻¹–a5⁻¹, 15–19: b1⁻¹–b5⁻¹
+// 0–9: a1, b1, ..., a5, b5 (GENERATOR_ENTRIES), 10–19: their inverses (adjugates)
 template GeneratorMatrix(idx) {
     signal output M[4];
     if (idx == 0) { M[0] <== 2; M[1] <== 1; M[2] <== 1; M[3] <== 1; }
     else if (idx == 1) { M[0] <== 3; M[1] <== 2; M[2] <== 1; M[3] <== 1; }
-    else if (idx == 2) { M[0] <== 5; M[1] <== 3; M[2] <== 2; M[3] <== 1; }
-    else if (idx == 3) { M[0] <== 7; M[1] <== 4; M[2] <== 3; M[3] <== 2; }
-    else if (idx == 4) { M[0] <== 11; M[1] <== 7; M[2] <== 4; M[3] <== 3; }
-    else if (idx == 5) { M[0] <== 13; M[1] <== 8; M[2] <== 5; M[3] <== 3; }
-    else if (idx == 6) { M[0] <== 17; M[1] <== 11; M[2] <== 7; M[3] <== 4; }
-    else if (idx == 7) { M[0] <== 19; M[1] <== 12; M[2] <== 8; M[3] <== 5; }
-    else if (idx == 8) { M[0] <== 23; M[1] <== 14; M[2] <== 9; M[3] <== 6; }
-    else if (idx == 9) { M[0] <== 21; M[1] <== 13; M[2] <== 8; M[3] <== 5; }
+    else if (idx == 2) { M[0] <== 29; M[1] <== 21; M[2] <== 11; M[3] <== 8; }
+    else if (idx == 3) { M[0] <== 7; M[1] <== 5; M[2] <== 4; M[3] <== 3; }
+    else if (idx == 4) { M[0] <== 3; M[1] <== 1; M[2] <== 2; M[3] <== 1; }
+    else if (idx == 5) { M[0] <== 4; M[1] <== 1; M[2] <== 3; M[3] <== 1; }
+    else if (idx == 6) { M[0] <== 71; M[1] <== 19; M[2] <== 56; M[3] <== 15; }
+    else if (idx == 7) { M[0] <== 15; M[1] <== 4; M[2] <== 11; M[3] <== 3; }
+    else if (idx == 8) { M[0] <== 2; M[1] <== 3; M[2] <== 3; M[3] <== 5; }
+    else if (idx == 9) { M[0] <== 5; M[1] <== 8; M[2] <== 8; M[3] <== 13; }
     else if (idx == 10) { M[0] <== 1; M[1] <== -1; M[2] <== -1; M[3] <== 2; }
     else if (idx == 11) { M[0] <== 1; M[1] <== -2; M[2] <== -1; M[3] <== 3; }
-    else if (idx == 12) { M[0] <== 1; M[1] <== -3; M[2] <== -2; M[3] <== 5; }
-    else if (idx == 13) { M[0] <== 2; M[1] <== -4; M[2] <== -3; M[3] <== 7; }
-    else if (idx == 14) { M[0] <== 3; M[1] <== -7; M[2] <== -4; M[3] <== 11; }
-    else if (idx == 15) { M[0] <== 3; M[1] <== -8; M[2] <== -5; M[3] <== 13; }
-    else if (idx == 16) { M[0] <== 4; M[1] <== -11; M[2] <== -7; M[3] <== 17; }
-    else if (idx == 17) { M[0] <== 5; M[1] <== -12; M[2] <== -8; M[3] <== 19; }
-    else if (idx == 18) { M[0] <== 6; M[1] <== -14; M[2] <== -9; M[3] <== 23; }
-    else if (idx == 19) { M[0] <== 5; M[1] <== -13; M[2] <== -8; M[3] <== 21; }
+    else if (idx == 12) { M[0] <== 8; M[1] <== -21; M[2] <== -11; M[3] <== 29; }
+    else if (idx == 13) { M[0] <== 3; M[1] <== -5; M[2] <== -4; M[3] <== 7; }
+    else if (idx == 14) { M[0] <== 1; M[1] <== -1; M[2] <== -2; M[3] <== 3; }
+    else if (idx == 15) { M[0] <== 1; M[1] <== -1; M[2] <== -3; M[3] <== 4; }
+    else if (idx == 16) { M[0] <== 15; M[1] <== -19; M[2] <== -56; M[3] <== 71; }
+    else if (idx == 17) { M[0] <== 3; M[1] <== -4; M[2] <== -11; M[3] <== 15; }
+    else if (idx == 18) { M[0] <== 5; M[1] <== -3; M[2] <== -3; M[3] <== 2; }
+    else if (idx == 19) { M[0] <== 13; M[1] <== -8; M[2] <== -8; M[3] <== 5; }
     else { M[0] <== 1; M[1] <== 0; M[2] <== 0; M[3] <== 1; }
 }
 
@@ -112,6 +113,25 @@ template ReducedPathCheck(pathLen) {
     }
 }
 
+// Enforce 0 <= indices[i] < bound (bound <= 32). GeneratorMatrix maps unknown
+// indices to the identity, so without this an index >= 20 is a free no-op step.
+// Each index is decomposed into 5 bits first: LessThan alone accepts field
+// elements that wrap around below the bound.
+template IndexRangeCheck(pathLen) {
+    signal input indices[pathLen];
+    signal input bound;
+    component bits[pathLen];
+    component lt[pathLen];
+    for (var i = 0; i < pathLen; i++) {
+        bits[i] = Num2Bits(5);
+        bits[i].in <== indices[i];
+        lt[i] = LessThan(5);
+        lt[i].in[0] <== indices[i];
+        lt[i].in[1] <== bound;
+        lt[i].out === 1;
+    }
+}
+
 // Main enhanced verification circuit
 template TopoShieldVerifyEnhanced() {
     // Public inputs
@@ -123,6 +143,19 @@ template TopoShieldVerifyEnhanced() {
     // Private witness
     signal private gamma[20];   // Secret path (generator indices 0–19)
     signal private delta[20];   // Message-dependent modifier
+    signal private num_generators; // Generator cardinality (10 generators + inverses)
+
+    // 0. Pin the generator cardinality to the lookup table in GeneratorMatrix
+    // and keep every path index inside it
+    num_generators === 20;
+
+    component range_gamma = IndexRangeCheck(20);
+    range_gamma.bound <== num_generators;
+    for (var i = 0; i < 20; i++) range_gamma.indices[i] <== gamma[i];
+
+    component range_delta = IndexRangeCheck(20);
+    range_delta.bound <== num_generators;
+    for (var i = 0; i < 20; i++) range_delta.indices[i] <== delta[i];
 
     // 1. Enforce reduced form for gamma and delta
     component check_gamma = ReducedPathCheck(20);
@@ -145,22 +178,26 @@ template TopoShieldVerifyEnhanced() {
     for (var i = 0; i < 40; i++) sigPath.indices[i] <== combined[i];
     for (var i = 0; i < 4; i++) sigPath.result[i] === H_sig[i];
 
-    // 4. Enhanced manifold descriptor: Poseidon(5, -8, 12345, tr(a1), tr(b1), ..., tr(b5))
-    component desc = Poseidon(13);
-    desc.in[0] <== 5;           // genus
-    desc.in[1] <== -8;          // Euler characteristic χ = 2 - 2g
-    desc.in[2] <== 12345;       // p-adic invariant
+    // 4. Enhanced manifold descriptor: the first 4 state elements of circomlib's
+    // Poseidon permutation (t = 15) over (DOMAIN_DESC, 5, -8, 12345, tr(a1), ..., tr(b5));
+    // `Poseidon(14)` only exposes the first, so desc_M uses `PoseidonEx(14, 4)`
+    component desc = PoseidonEx(14, 4);
+    desc.initialState <== 0;
+    desc.in[0] <== 1;           // DOMAIN_DESC (src/manifold.rs)
+    desc.in[1] <== 5;           // genus
+    desc.in[2] <== -8;          // Euler characteristic χ = 2 - 2g
+    desc.in[3] <== 12345;       // p-adic invariant
     // Traces of the 10 positive generators (a1 to b5)
-    desc.in[3] <== 2 + 1;       // tr(a1) = 2 + 1 = 3
-    desc.in[4] <== 3 + 1;       // tr(b1) = 4
-    desc.in[5] <== 5 + 1;       // tr(a2) = 6
-    desc.in[6] <== 7 + 2;       // tr(b2) = 9
-    desc.in[7] <== 11 + 3;      // tr(a3) = 14
-    desc.in[8] <== 13 + 3;      // tr(b3) = 16
-    desc.in[9] <== 17 + 4;      // tr(a4) = 21
-    desc.in[10] <== 19 + 5;     // tr(b4) = 24
-    desc.in[11] <== 23 + 6;     // tr(a5) = 29
-    desc.in[12] <== 21 + 5;     // tr(b5) = 26
+    desc.in[4] <== 3;           // tr(a1)
+    desc.in[5] <== 4;           // tr(b1)
+    desc.in[6] <== 37;          // tr(a2)
+    desc.in[7] <== 10;          // tr(b2)
+    desc.in[8] <== 4;           // tr(a3)
+    desc.in[9] <== 5;           // tr(b3)
+    desc.in[10] <== 86;         // tr(a4)
+    desc.in[11] <== 18;         // tr(b4)
+    desc.in[12] <== 7;          // tr(a5)
+    desc.in[13] <== 18;         // tr(b5)
     for (var i = 0; i < 4; i++) desc.out[i] === desc_M[i];
 }
 
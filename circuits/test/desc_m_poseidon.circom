@@ -0,0 +1,17 @@
+// circuits/test/desc_m_poseidon.circom
+// Test harness: the desc_M hash of holonomy_path_enhanced.circom on its own, with the
+// preimage as an input, so tests can read the circuit's Poseidon output directly
+
+include "circomlib/circuits/poseidon.circom";
+
+template DescMPoseidon() {
+    signal input in[14];  // DOMAIN_DESC, genus, χ, p_inv, tr(a1), ..., tr(b5)
+    signal output out[4];
+
+    component desc = PoseidonEx(14, 4);
+    desc.initialState <== 0;
+    for (var i = 0; i < 14; i++) desc.in[i] <== in[i];
+    for (var i = 0; i < 4; i++) out[i] <== desc.out[i];
+}
+
+component main = DescMPoseidon();
@@ -0,0 +1,17 @@
+// fuzz/fuzz_targets/fuzz_witness_deserialize.rs
+// Untrusted witness JSON must deserialize or fail cleanly, and structural checks
+// must return a Result for any deserialized witness — never panic.
+// Run with: cargo +nightly fuzz run fuzz_witness_deserialize
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use toposhield::{manifold::HyperbolicManifold, witness::Witness};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(witness) = serde_json::from_slice::<Witness>(data) else {
+        return;
+    };
+    let manifold = HyperbolicManifold::new();
+    let _ = witness.verify_structural(&manifold);
+    let _ = witness.to_circom_input();
+});
@@ -1,7 +1,7 @@
 // src/bin/prove-example.rs
 // Example: generate a TopoShield ZK proof for a sample message
 use std::fs;
-use toposhield::{witness::Witness, prover::TopoShieldProver};
+use toposhield::{prover::TopoShieldProver, secret::SecretSeed, witness::Witness};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 1. Create prover (loads Circom artifacts and KZG setup)
@@ -9,15 +9,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // 2. Generate witness (signing)
     let message = b"TopoShield proof example — genus=5, enhanced ZKP";
-    let private_seed = b"example_seed_2025";
-    let witness = Witness::new(message, private_seed);
+    let private_seed = SecretSeed::from_env("TOPOSHIELD_SEED").map_err(|e| {
+        format!("{} — set TOPOSHIELD_SEED to your private signing seed", e)
+    })?;
+    let witness = Witness::new(message, private_seed.expose());
 
     // 3. Generate ZK proof
-    let proof = prover.prove(witness)?;
+    let proof = prover.prove(witness.clone())?;
 
     // 4. Save proof to disk
-    fs::write("proof.bin", &proof)?;
-    println!("✅ Proof saved to proof.bin ({} bytes)", proof.len());
+    fs::write("proof.bin", &proof.bytes)?;
+    println!("✅ Proof saved to proof.bin ({} bytes)", proof.bytes.len());
 
     // 5. Optional: verify proof
     let is_valid = prover.verify(
@@ -0,0 +1,184 @@
+// src/bin/prove-server.rs
+// HTTP proving service: loads the TopoShield prover once and serves /prove and /verify
+// Build with: cargo run --bin prove-server --features server --release [-- ADDR]
+// /prove receives the signer's seed in plaintext, so it is only served on loopback
+// addresses; bound elsewhere, the server answers /verify alone.
+use std::{io::Read, net::ToSocketAddrs, sync::Arc, thread};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use ff::Field;
+use halo2_proofs::halo2curves::bn256::Fr;
+use serde::{Deserialize, Serialize};
+use tiny_http::{Header, Method, Request, Response, Server};
+use toposhield::{
+    prover::{fr_from_hex, fr_to_hex, Proof, TopoShieldProver},
+    witness::Witness,
+};
+
+const DEFAULT_ADDR: &str = "127.0.0.1:8080";
+
+/// Largest request body read; a /verify request is a few KiB
+const MAX_BODY_BYTES: u64 = 64 * 1024;
+
+#[derive(Deserialize)]
+struct ProveRequest {
+    message: String,
+    seed: String,
+}
+
+#[derive(Serialize)]
+struct ProveResponse {
+    proof: String,
+    poseidon_spec_id: String,
+    h_pub: Vec<String>,
+    h_sig: Vec<String>,
+    desc_m: Vec<String>,
+    m_hash: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct VerifyRequest {
+    proof: String,
+    poseidon_spec_id: String,
+    h_pub: Vec<String>,
+    h_sig: Vec<String>,
+    desc_m: Vec<String>,
+    m_hash: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct VerifyResponse {
+    valid: bool,
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let addr = std::env::args().nth(1).unwrap_or_else(|| DEFAULT_ADDR.to_string());
+
+    // 1. Load the prover once (R1CS, WASM, KZG params, keygen) and share it across workers
+    let prover = Arc::new(TopoShieldProver::new()?);
+
+    // 2. Bind the HTTP server; /prove only on loopback, where the seed stays local
+    let serve_prove = addr.to_socket_addrs()?.all(|a| a.ip().is_loopback());
+    let server = Arc::new(Server::http(&addr)?);
+    println!("✅ TopoShield prove-server listening on http://{}", addr);
+    if !serve_prove {
+        println!("⚠️  {} is not a loopback address: /prove is disabled, /verify only", addr);
+    }
+
+    // 3. Serve requests from a pool of worker threads.
+    // `prove` and `verify` take `&self`, so all workers share one prover.
+    let workers = thread::available_parallelism().map(|n| n.get()).unwrap_or(4);
+    let handles: Vec<_> = (0..workers)
+        .map(|_| {
+            let server = Arc::clone(&server);
+            let prover = Arc::clone(&prover);
+            thread::spawn(move || {
+                while let Ok(request) = server.recv() {
+                    handle(&prover, request, serve_prove);
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        let _ = handle.join();
+    }
+    Ok(())
+}
+
+/// Dispatch a single request and write the JSON response
+fn handle(prover: &TopoShieldProver, mut request: Request, serve_prove: bool) {
+    let mut body = String::new();
+    let read = request
+        .as_reader()
+        .take(MAX_BODY_BYTES + 1)
+        .read_to_string(&mut body);
+    let result = match read {
+        Ok(n) if n as u64 > MAX_BODY_BYTES => {
+            Err((413, format!("request body exceeds {} bytes", MAX_BODY_BYTES)))
+        }
+        Ok(_) => match (request.method(), request.url()) {
+            (Method::Post, "/prove") if serve_prove => handle_prove(prover, &body),
+            (Method::Post, "/prove") => Err((403, "/prove is only served on loopback addresses".to_string())),
+            (Method::Post, "/verify") => handle_verify(prover, &body),
+            _ => Err((404, "not found".to_string())),
+        },
+        Err(e) => Err((400, format!("failed to read request body: {}", e))),
+    };
+
+    let (status, json) = match result {
+        Ok(json) => (200, json),
+        Err((status, error)) => (
+            status,
+            serde_json::to_string(&ErrorResponse { error }).unwrap_or_default(),
+        ),
+    };
+    let header = Header::from_bytes("Content-Type", "application/json")
+        .expect("static header is valid");
+    let response = Response::from_string(json)
+        .with_status_code(status)
+        .with_header(header);
+    if let Err(e) = request.respond(response) {
+        eprintln!("⚠️  Failed to send response: {}", e);
+    }
+}
+
+/// POST /prove — {message, seed} → {proof (base64), public inputs (hex)}
+fn handle_prove(prover: &TopoShieldProver, body: &str) -> Result<String, (u16, String)> {
+    let req: ProveRequest =
+        serde_json::from_str(body).map_err(|e| (400, format!("invalid request: {}", e)))?;
+
+    let witness = Witness::new(req.message.as_bytes(), req.seed.as_bytes());
+    let proof = prover
+        .prove(witness.clone())
+        .map_err(|e| (500, format!("proving failed: {}", e)))?;
+
+    let response = ProveResponse {
+        proof: BASE64.encode(&proof.bytes),
+        poseidon_spec_id: fr_to_hex(&proof.poseidon_spec_id),
+        h_pub: witness.h_pub.iter().map(fr_to_hex).collect(),
+        h_sig: witness.h_sig.iter().map(fr_to_hex).collect(),
+        desc_m: witness.desc_m.iter().map(fr_to_hex).collect(),
+        m_hash: witness.m_hash.iter().map(fr_to_hex).collect(),
+    };
+    serde_json::to_string(&response).map_err(|e| (500, e.to_string()))
+}
+
+/// POST /verify — {proof (base64), public inputs (hex)} → {valid}
+fn handle_verify(prover: &TopoShieldProver, body: &str) -> Result<String, (u16, String)> {
+    let req: VerifyRequest =
+        serde_json::from_str(body).map_err(|e| (400, format!("invalid request: {}", e)))?;
+
+    let bytes = BASE64
+        .decode(&req.proof)
+        .map_err(|e| (400, format!("invalid base64 proof: {}", e)))?;
+    let poseidon_spec_id = fr_from_hex(&req.poseidon_spec_id)
+        .ok_or_else(|| (400, "invalid poseidon_spec_id".to_string()))?;
+    let proof = Proof { bytes, poseidon_spec_id };
+    let h_pub = parse_component("h_pub", &req.h_pub)?;
+    let h_sig = parse_component("h_sig", &req.h_sig)?;
+    let desc_m = parse_component("desc_m", &req.desc_m)?;
+    let m_hash = parse_component("m_hash", &req.m_hash)?;
+
+    let valid = prover
+        .verify(&proof, h_pub, h_sig, desc_m, m_hash)
+        .map_err(|e| (400, format!("verification error: {}", e)))?;
+    serde_json::to_string(&VerifyResponse { valid }).map_err(|e| (500, e.to_string()))
+}
+
+fn parse_component(name: &str, values: &[String]) -> Result<[Fr; 4], (u16, String)> {
+    if values.len() != 4 {
+        return Err((400, format!("{} must have 4 elements, got {}", name, values.len())));
+    }
+    let mut out = [Fr::zero(); 4];
+    for (slot, value) in out.iter_mut().zip(values) {
+        *slot = fr_from_hex(value)
+            .ok_or_else(|| (400, format!("{}: invalid field element {}", name, value)))?;
+    }
+    Ok(out)
+}
@@ -1,22 +1,107 @@
 // src/bin/setup-kzg.rs
 // Generate KZG trusted setup for TopoShield (k=17 → ~131k constraints)
+// Pass --json for a machine-readable status object on stdout
 use std::fs;
 use halo2_proofs::poly::kzg::commitment::ParamsKZG;
 use halo2_proofs::halo2curves::bn256::Bn256;
-use rand::rngs::OsRng;
+use rand::{rngs::OsRng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use halo2_circom::circuit::CircomConfig;
+use toposhield::prover::{available_memory, estimate_srs_memory, minimum_k_for_circuit};
+
+const K: u32 = 17;
+const PARAMS_PATH: &str = "params/kzg.srs";
+const R1CS_PATH: &str = "build/holonomy_path_enhanced.r1cs";
+const WASM_PATH: &str = "build/holonomy_path_enhanced.wasm";
+
+/// Status emitted with --json
+#[derive(Serialize)]
+struct SetupStatus {
+    completed: bool,
+    k: u32,
+    path: &'static str,
+    sha256: String,
+    seeded: bool,
+}
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let json = std::env::args().skip(1).any(|arg| arg == "--json");
+
+    // The prover accepts any k that fits the circuit; check that K does and report the margin
+    match CircomConfig::<Bn256>::new(R1CS_PATH, WASM_PATH) {
+        Ok(config) => {
+            let minimum_k = minimum_k_for_circuit(&config.r1cs);
+            if minimum_k > K {
+                return Err(format!("circuit needs k ≥ {}, but setup generates k = {}", minimum_k, K).into());
+            }
+            eprintln!("   Circuit needs k ≥ {}; generating k = {}", minimum_k, K);
+        }
+        Err(_) => eprintln!("⚠️  {} not built; cannot check the circuit fits k = {}", R1CS_PATH, K),
+    }
+
+    // Refuse to start a setup that would run out of memory halfway
+    let required = estimate_srs_memory(K);
+    let mib = |bytes: usize| bytes as f64 / (1024.0 * 1024.0);
+    match available_memory() {
+        Some(available) if available < required => {
+            return Err(format!(
+                "k={} needs about {:.1} MiB but only {:.1} MiB is available; aborting setup",
+                K,
+                mib(required),
+                mib(available)
+            )
+            .into());
+        }
+        Some(available) => eprintln!(
+            "   Estimated SRS memory (k={}): {:.1} MiB of {:.1} MiB available",
+            K,
+            mib(required),
+            mib(available)
+        ),
+        None => eprintln!("   Estimated SRS memory (k={}): {:.1} MiB", K, mib(required)),
+    }
+
     // Ensure params directory exists
     fs::create_dir_all("params")?;
 
     // Generate KZG SRS (k=17 supports up to 2^17 = 131072 constraints)
     // holonomy_path_enhanced.circom uses ~50k constraints → k=17 is sufficient
-    let params = ParamsKZG::<Bn256>::setup(17, OsRng);
+    // TOPOSHIELD_SETUP_SEED (64 hex chars) replaces OsRng for sandboxes without OS entropy.
+    // The seed determines the toxic waste: it must be secret and destroyed after setup.
+    let seed_var = std::env::var("TOPOSHIELD_SETUP_SEED");
+    let seeded = seed_var.is_ok();
+    let params = match seed_var {
+        Ok(seed_hex) => {
+            let seed: [u8; 32] = hex::decode(seed_hex.trim())?
+                .try_into()
+                .map_err(|_| "TOPOSHIELD_SETUP_SEED must be 32 bytes (64 hex chars)")?;
+            eprintln!("⚠️  Using seeded ChaCha20 RNG for setup — keep the seed secret and destroy it");
+            ParamsKZG::<Bn256>::setup(K, ChaCha20Rng::from_seed(seed))
+        }
+        Err(_) => ParamsKZG::<Bn256>::setup(K, OsRng),
+    };
 
     // Save to file
-    let mut file = fs::File::create("params/kzg.srs")?;
-    params.write(&mut file)?;
+    let mut bytes = Vec::new();
+    params.write(&mut bytes)?;
+    fs::write(PARAMS_PATH, &bytes)?;
+    let sha256 = hex::encode(Sha256::digest(&bytes));
+    fs::write(format!("{}.sha256", PARAMS_PATH), format!("{}  kzg.srs\n", sha256))?;
 
-    println!("✅ KZG trusted setup (k=17) generated and saved to params/kzg.srs");
+    if json {
+        let status = SetupStatus {
+            completed: true,
+            k: K,
+            path: PARAMS_PATH,
+            sha256,
+            seeded,
+        };
+        println!("{}", serde_json::to_string(&status)?);
+    } else {
+        println!("✅ KZG trusted setup (k={}) generated and saved to {}", K, PARAMS_PATH);
+        println!("   SHA-256: {}", sha256);
+    }
     Ok(())
 }
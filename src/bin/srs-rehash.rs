@@ -0,0 +1,40 @@
+// src/bin/srs-rehash.rs
+// Recompute params/kzg.srs.sha256 after regenerating the SRS locally
+// Usage: srs-rehash [PATH] [--yes]
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use toposhield::prover::{params_hash_path, write_params_hash};
+
+const PARAMS_PATH: &str = "params/kzg.srs";
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let yes = args.iter().any(|arg| arg == "--yes");
+    let path = args
+        .iter()
+        .find(|arg| !arg.starts_with("--"))
+        .map_or(PARAMS_PATH, String::as_str);
+    let path = Path::new(path);
+
+    // Rehashing accepts the current file as trusted, so any tampering since the
+    // last hash goes undetected
+    if !yes {
+        eprint!(
+            "⚠️  This trusts the current contents of {} and overwrites {}.\n   Continue? [y/N] ",
+            path.display(),
+            params_hash_path(path).display()
+        );
+        io::stderr().flush()?;
+        let mut answer = String::new();
+        io::stdin().lock().read_line(&mut answer)?;
+        if !matches!(answer.trim(), "y" | "Y" | "yes") {
+            eprintln!("Aborted; hash file unchanged");
+            return Ok(());
+        }
+    }
+
+    let digest = write_params_hash(path)?;
+    println!("✅ {} rehashed", path.display());
+    println!("   SHA-256: {}", digest);
+    Ok(())
+}
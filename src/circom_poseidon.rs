@@ -0,0 +1,229 @@
+// src/circom_poseidon.rs
+// circomlib's Poseidon over BN254, for values the circuit recomputes (desc_M)
+// Parameters are regenerated with the Grain LFSR of the Poseidon reference script
+use ff::{Field, PrimeField};
+use halo2_proofs::halo2curves::bn256::Fr;
+
+/// Full rounds, half before and half after the partial rounds
+const FULL_ROUNDS: usize = 8;
+
+/// Partial rounds of circomlib's `Poseidon(n)` by state width t = n + 1, from t = 2
+const PARTIAL_ROUNDS: [usize; 16] = [56, 57, 56, 60, 60, 63, 64, 63, 60, 66, 60, 65, 70, 60, 64, 68];
+
+/// Bit length of the BN254 scalar field modulus
+const FIELD_BITS: usize = 254;
+
+/// circomlib's Poseidon permutation for one state width: S-box x⁵, 8 full rounds,
+/// round constants and a Cauchy MDS matrix sampled from the reference Grain LFSR.
+/// The reference script also screens the matrix for invariant subspace trails; that
+/// screen is not reimplemented, so widths are pinned against circomlib outputs (see
+/// the tests here and `test_desc_m_matches_circuit_poseidon`).
+#[derive(Debug, Clone)]
+pub struct CircomPoseidon {
+    width: usize,
+    partial_rounds: usize,
+    round_constants: Vec<Fr>,
+    mds: Vec<Vec<Fr>>,
+}
+
+impl CircomPoseidon {
+    /// Parameters of circomlib's `Poseidon(n_inputs)`, state width n_inputs + 1.
+    /// Panics unless 1 ≤ n_inputs ≤ 16, the widths circomlib ships.
+    pub fn new(n_inputs: usize) -> Self {
+        assert!(
+            (1..=PARTIAL_ROUNDS.len()).contains(&n_inputs),
+            "circomlib Poseidon takes 1 to 16 inputs, got {}",
+            n_inputs
+        );
+        let width = n_inputs + 1;
+        let partial_rounds = PARTIAL_ROUNDS[width - 2];
+        let mut grain = Grain::new(width, partial_rounds);
+        let round_constants = (0..(FULL_ROUNDS + partial_rounds) * width)
+            .map(|_| grain.next_field_element())
+            .collect();
+        let mds = grain.cauchy_matrix(width);
+        Self {
+            width,
+            partial_rounds,
+            round_constants,
+            mds,
+        }
+    }
+
+    /// circomlib `Poseidon(n)`: the first state element after permuting [0, inputs...]
+    pub fn hash(&self, inputs: &[Fr]) -> Fr {
+        self.hash_ex(inputs, 1)[0]
+    }
+
+    /// circomlib `PoseidonEx(n, n_outs)` with `initialState = 0`: the first `n_outs`
+    /// state elements after permuting [0, inputs...]. Panics if `inputs` does not
+    /// fill the rate or `n_outs` exceeds the width.
+    pub fn hash_ex(&self, inputs: &[Fr], n_outs: usize) -> Vec<Fr> {
+        assert_eq!(inputs.len() + 1, self.width, "expected {} inputs", self.width - 1);
+        assert!(n_outs <= self.width, "at most {} outputs", self.width);
+        let mut state = Vec::with_capacity(self.width);
+        state.push(Fr::zero());
+        state.extend_from_slice(inputs);
+        self.permute(&mut state);
+        state.truncate(n_outs);
+        state
+    }
+
+    fn permute(&self, state: &mut [Fr]) {
+        let first_partial = FULL_ROUNDS / 2;
+        for (round, constants) in self.round_constants.chunks(self.width).enumerate() {
+            for (x, c) in state.iter_mut().zip(constants) {
+                *x += c;
+            }
+            if round < first_partial || round >= first_partial + self.partial_rounds {
+                state.iter_mut().for_each(|x| *x = quintic(*x));
+            } else {
+                state[0] = quintic(state[0]);
+            }
+            let mixed: Vec<Fr> = self
+                .mds
+                .iter()
+                .map(|row| row.iter().zip(state.iter()).fold(Fr::zero(), |acc, (m, x)| acc + *m * x))
+                .collect();
+            state.copy_from_slice(&mixed);
+        }
+    }
+}
+
+fn quintic(x: Fr) -> Fr {
+    x.square().square() * x
+}
+
+/// Grain LFSR of the Poseidon reference script (generate_parameters_grain.sage) for
+/// a prime field and S-box x^α; bit i of `state` is the i-th oldest bit
+struct Grain {
+    state: u128,
+}
+
+impl Grain {
+    fn new(width: usize, partial_rounds: usize) -> Self {
+        // 80 bits, most significant first: field type 1 (prime), S-box 0 (x^α),
+        // field size, t, R_F, R_P, then 30 ones
+        let fields: [(usize, usize); 7] = [
+            (1, 2),
+            (0, 4),
+            (FIELD_BITS, 12),
+            (width, 12),
+            (FULL_ROUNDS, 10),
+            (partial_rounds, 10),
+            ((1 << 30) - 1, 30),
+        ];
+        let mut grain = Self { state: 0 };
+        let mut position = 0;
+        for (value, bits) in fields {
+            for i in (0..bits).rev() {
+                grain.state |= (((value >> i) & 1) as u128) << position;
+                position += 1;
+            }
+        }
+        for _ in 0..160 {
+            grain.step();
+        }
+        grain
+    }
+
+    fn step(&mut self) -> bool {
+        let s = self.state;
+        let bit = ((s >> 62) ^ (s >> 51) ^ (s >> 38) ^ (s >> 23) ^ (s >> 13) ^ s) & 1;
+        self.state = (s >> 1) | (bit << 79);
+        bit == 1
+    }
+
+    /// Output bits are filtered in pairs: (1, b) yields b, (0, b) yields nothing
+    fn next_bit(&mut self) -> bool {
+        loop {
+            let keep = self.step();
+            let bit = self.step();
+            if keep {
+                return bit;
+            }
+        }
+    }
+
+    /// FIELD_BITS output bits, most significant first, as a little-endian repr
+    fn next_repr(&mut self) -> [u8; 32] {
+        let mut repr = [0u8; 32];
+        for position in (0..FIELD_BITS).rev() {
+            if self.next_bit() {
+                repr[position / 8] |= 1 << (position % 8);
+            }
+        }
+        repr
+    }
+
+    /// Round constant: samples ≥ p are rejected
+    fn next_field_element(&mut self) -> Fr {
+        loop {
+            if let Some(x) = Option::from(Fr::from_repr(self.next_repr())) {
+                return x;
+            }
+        }
+    }
+
+    /// MDS sample: reduced mod p, not rejected
+    fn next_reduced(&mut self) -> Fr {
+        let repr = self.next_repr();
+        repr.iter()
+            .rev()
+            .fold(Fr::zero(), |acc, &byte| acc * Fr::from(256u64) + Fr::from(byte as u64))
+    }
+
+    /// M[i][j] = 1 / (x_i + y_j) over 2t distinct samples, resampled until every
+    /// x_i + y_j is invertible
+    fn cauchy_matrix(&mut self, width: usize) -> Vec<Vec<Fr>> {
+        loop {
+            let mut sample: Vec<Fr> = (0..2 * width).map(|_| self.next_reduced()).collect();
+            while (1..sample.len()).any(|i| sample[..i].contains(&sample[i])) {
+                sample = (0..2 * width).map(|_| self.next_reduced()).collect();
+            }
+            let (xs, ys) = sample.split_at(width);
+            let matrix: Option<Vec<Vec<Fr>>> = xs
+                .iter()
+                .map(|x| ys.iter().map(|y| Option::from((*x + y).invert())).collect())
+                .collect();
+            if let Some(matrix) = matrix {
+                return matrix;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_hex(hex_str: &str) -> Fr {
+        let mut repr = [0u8; 32];
+        repr.copy_from_slice(&hex::decode(hex_str).unwrap());
+        repr.reverse();
+        Fr::from_repr(repr).unwrap()
+    }
+
+    #[test]
+    fn test_matches_circomlib_vectors() {
+        // circomlibjs poseidon([1, 2]) and poseidon([1, 2, 3, 4])
+        let inputs: Vec<Fr> = (1..=4u64).map(Fr::from).collect();
+        assert_eq!(
+            CircomPoseidon::new(2).hash(&inputs[..2]),
+            from_hex("115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a")
+        );
+        assert_eq!(
+            CircomPoseidon::new(4).hash(&inputs),
+            from_hex("299c867db6c1fdd79dcefa40e4510b9837e60ebb1ce0663dbaa525df65250465")
+        );
+    }
+
+    #[test]
+    fn test_hash_ex_extends_hash() {
+        let poseidon = CircomPoseidon::new(3);
+        let inputs = [Fr::from(7u64), Fr::from(8u64), Fr::from(9u64)];
+        let outs = poseidon.hash_ex(&inputs, 4);
+        assert_eq!(outs.len(), 4);
+        assert_eq!(outs[0], poseidon.hash(&inputs));
+    }
+}
@@ -0,0 +1,137 @@
+// src/context.rs
+// Serializable signing context: everything needed to regenerate a witness
+// A redacted context (seed commitment only) still lets an auditor check the public parts
+use crate::{
+    manifold::{HyperbolicManifold, DESCRIPTOR_VERSION},
+    witness::{Witness, PATH_LENGTH},
+};
+use halo2_proofs::halo2curves::bn256::Fr;
+use serde::{Deserialize, Serialize};
+
+/// Inputs and system parameters of a single `Witness::new` call
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct SigningContext {
+    pub message: Vec<u8>,
+    /// Private seed; `None` in a redacted context
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<Vec<u8>>,
+    /// Poseidon commitment to the seed, kept when the seed is redacted
+    pub seed_commitment: [Fr; 4],
+    pub genus: u32,
+    pub chi: i32,
+    pub p_inv: u64,
+    pub path_length: usize,
+    pub poseidon_spec_id: Fr,
+    pub descriptor_version: u32,
+}
+
+impl SigningContext {
+    /// Capture the context of signing `message` with `seed` on the system manifold
+    pub fn new(message: &[u8], seed: &[u8]) -> Self {
+        let manifold = HyperbolicManifold::new();
+        Self {
+            message: message.to_vec(),
+            seed: Some(seed.to_vec()),
+            seed_commitment: Witness::seed_commitment(seed),
+            genus: manifold.genus,
+            chi: manifold.chi,
+            p_inv: manifold.p_inv,
+            path_length: PATH_LENGTH,
+            poseidon_spec_id: Witness::poseidon_spec_id(),
+            descriptor_version: DESCRIPTOR_VERSION,
+        }
+    }
+
+    /// Copy of the context with the seed removed, safe to hand to an auditor
+    pub fn redacted(&self) -> Self {
+        Self {
+            seed: None,
+            ..self.clone()
+        }
+    }
+
+    /// True if the recorded parameters are the ones this build signs with
+    pub fn matches_environment(&self) -> bool {
+        let manifold = HyperbolicManifold::new();
+        self.genus == manifold.genus
+            && self.chi == manifold.chi
+            && self.p_inv == manifold.p_inv
+            && self.path_length == PATH_LENGTH
+            && self.poseidon_spec_id == Witness::poseidon_spec_id()
+            && self.descriptor_version == DESCRIPTOR_VERSION
+    }
+
+    /// Regenerate the exact witness. `None` if the context is redacted, the seed
+    /// doesn't match its commitment, or the parameters differ from this build.
+    pub fn reproduce(&self) -> Option<Witness> {
+        let seed = self.seed.as_ref()?;
+        if Witness::seed_commitment(seed) != self.seed_commitment || !self.matches_environment() {
+            return None;
+        }
+        Some(Witness::new(&self.message, seed))
+    }
+
+    /// Check the public parts of `witness` against the context without the seed:
+    /// parameters, message hash and descriptor
+    pub fn verify_public(&self, witness: &Witness) -> bool {
+        self.matches_environment()
+            && witness.m_hash == Witness::hash_to_4fr(&self.message)
+            && witness.desc_m == HyperbolicManifold::new().descriptor()
+    }
+}
+
+impl std::fmt::Debug for SigningContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SigningContext")
+            .field("message", &self.message)
+            .field("seed", &self.seed.as_ref().map(|s| format!("<{} bytes redacted>", s.len())))
+            .field("seed_commitment", &self.seed_commitment)
+            .field("genus", &self.genus)
+            .field("chi", &self.chi)
+            .field("p_inv", &self.p_inv)
+            .field("path_length", &self.path_length)
+            .field("poseidon_spec_id", &self.poseidon_spec_id)
+            .field("descriptor_version", &self.descriptor_version)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signing_context_roundtrip() {
+        let context = SigningContext::new(b"Audit me", b"context_seed");
+        let json = serde_json::to_string(&context).unwrap();
+        let restored: SigningContext = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, context);
+
+        let reproduced = restored.reproduce().expect("full context reproduces");
+        let original = Witness::new(b"Audit me", b"context_seed");
+        assert_eq!(reproduced.h_pub, original.h_pub);
+        assert_eq!(reproduced.h_sig, original.h_sig);
+        assert_eq!(reproduced.gamma, original.gamma);
+        assert_eq!(reproduced.delta, original.delta);
+    }
+
+    #[test]
+    fn test_redacted_context() {
+        let context = SigningContext::new(b"Audit me", b"context_seed");
+        let redacted = context.redacted();
+        let json = serde_json::to_string(&redacted).unwrap();
+        assert!(!json.contains("\"seed\""));
+        assert!(!format!("{:?}", context).contains("context_seed"));
+
+        let restored: SigningContext = serde_json::from_str(&json).unwrap();
+        assert!(restored.reproduce().is_none());
+        let witness = context.reproduce().unwrap();
+        assert!(restored.verify_public(&witness));
+        assert!(!restored.verify_public(&Witness::new(b"Other message", b"context_seed")));
+
+        // A seed that doesn't open the commitment is refused
+        let mut forged = context.clone();
+        forged.seed = Some(b"other_seed".to_vec());
+        assert!(forged.reproduce().is_none());
+    }
+}
@@ -0,0 +1,65 @@
+// src/descriptor.rs
+// Registry of accepted manifold descriptor versions for verifiers
+// Lets a verifier accept proofs from several descriptor generations during an upgrade
+use crate::manifold::{HyperbolicManifold, DESCRIPTOR_VERSION};
+use halo2_proofs::halo2curves::bn256::Fr;
+use std::collections::BTreeMap;
+
+/// Accepted descriptor versions and their expected desc_M constants
+#[derive(Debug, Clone, PartialEq)]
+pub struct DescriptorCompat {
+    versions: BTreeMap<u32, [Fr; 4]>,
+}
+
+impl Default for DescriptorCompat {
+    /// Accepts only the current descriptor of the canonical genus-5 manifold
+    fn default() -> Self {
+        Self::empty().with_version(DESCRIPTOR_VERSION, HyperbolicManifold::new().descriptor())
+    }
+}
+
+impl DescriptorCompat {
+    /// A registry that accepts nothing
+    pub fn empty() -> Self {
+        Self {
+            versions: BTreeMap::new(),
+        }
+    }
+
+    /// Register (or replace) the expected desc_M for a descriptor version
+    pub fn with_version(mut self, version: u32, desc_m: [Fr; 4]) -> Self {
+        self.versions.insert(version, desc_m);
+        self
+    }
+
+    /// Version whose descriptor equals `desc_m`, if any is registered
+    pub fn accepts(&self, desc_m: &[Fr; 4]) -> Option<u32> {
+        self.versions
+            .iter()
+            .find(|(_, registered)| *registered == desc_m)
+            .map(|(version, _)| *version)
+    }
+
+    pub fn versions(&self) -> impl Iterator<Item = u32> + '_ {
+        self.versions.keys().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_accepts_registered_versions() {
+        let current = HyperbolicManifold::new().descriptor();
+        let legacy = [Fr::from(1), Fr::from(2), Fr::from(3), Fr::from(4)];
+        let unknown = [Fr::from(5), Fr::from(6), Fr::from(7), Fr::from(8)];
+
+        let compat = DescriptorCompat::default().with_version(0, legacy);
+        assert_eq!(compat.accepts(&current), Some(DESCRIPTOR_VERSION));
+        assert_eq!(compat.accepts(&legacy), Some(0));
+        assert_eq!(compat.accepts(&unknown), None);
+        assert_eq!(compat.versions().collect::<Vec<_>>(), vec![0, DESCRIPTOR_VERSION]);
+        assert_eq!(DescriptorCompat::empty().accepts(&current), None);
+    }
+}
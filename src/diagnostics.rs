@@ -0,0 +1,172 @@
+// src/diagnostics.rs
+// Stage-by-stage diagnosis of the proving pipeline for a single witness
+// Mirrors the integration-test lifecycle as a reusable library routine
+use crate::{
+    manifold::HyperbolicManifold,
+    prover::{Proof, TopoShieldProver},
+    witness::Witness,
+};
+use ff::Field;
+use halo2_proofs::halo2curves::bn256::Fr;
+
+/// Pipeline stages, in execution order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticStage {
+    /// `Witness::verify_structural` against the canonical manifold
+    Structural,
+    /// MockProver constraint check
+    MockProver,
+    /// Real KZG proof generation
+    ProofGeneration,
+    /// Verification of the generated proof with the witness's public inputs
+    SelfVerification,
+    /// Verification must fail with a modified h_pub
+    TamperPublicKey,
+    /// Verification must fail with a modified h_sig
+    TamperSignature,
+    /// Verification must fail with a modified m_hash
+    TamperMessageHash,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StageOutcome {
+    Passed,
+    Failed(String),
+    /// Not run because an earlier stage failed
+    Skipped,
+}
+
+/// Per-stage results of `TopoShieldProver::diagnose`
+#[derive(Debug, Clone)]
+pub struct DiagnosticReport {
+    pub stages: Vec<(DiagnosticStage, StageOutcome)>,
+}
+
+impl DiagnosticReport {
+    pub fn all_passed(&self) -> bool {
+        self.stages.iter().all(|(_, outcome)| *outcome == StageOutcome::Passed)
+    }
+
+    /// First failing stage and its error details
+    pub fn first_failure(&self) -> Option<(DiagnosticStage, &str)> {
+        self.stages.iter().find_map(|(stage, outcome)| match outcome {
+            StageOutcome::Failed(reason) => Some((*stage, reason.as_str())),
+            _ => None,
+        })
+    }
+
+    pub fn outcome(&self, stage: DiagnosticStage) -> Option<&StageOutcome> {
+        self.stages.iter().find(|(s, _)| *s == stage).map(|(_, outcome)| outcome)
+    }
+}
+
+impl TopoShieldProver {
+    /// Run the full lifecycle for `witness` and collect per-stage pass/fail details
+    /// instead of stopping at an opaque error. Stages after a failure are `Skipped`.
+    pub fn diagnose(&self, witness: &Witness) -> DiagnosticReport {
+        let mut stages = Vec::new();
+        let proof = self.diagnose_until_proof(witness, &mut stages);
+
+        match proof {
+            Some(proof) => {
+                let verify = |h_pub, h_sig, m_hash| {
+                    self.verify(&proof, h_pub, h_sig, witness.desc_m, m_hash)
+                        .map_err(|e| e.to_string())
+                };
+                let self_check = match verify(witness.h_pub, witness.h_sig, witness.m_hash) {
+                    Ok(true) => StageOutcome::Passed,
+                    Ok(false) => StageOutcome::Failed("proof rejected with its own public inputs".into()),
+                    Err(e) => StageOutcome::Failed(e),
+                };
+                stages.push((DiagnosticStage::SelfVerification, self_check));
+
+                let tamper = |result: Result<bool, String>| match result {
+                    Ok(false) => StageOutcome::Passed,
+                    Ok(true) => StageOutcome::Failed("tampered public inputs were accepted".into()),
+                    Err(e) => StageOutcome::Failed(e),
+                };
+                stages.push((
+                    DiagnosticStage::TamperPublicKey,
+                    tamper(verify(bump(witness.h_pub), witness.h_sig, witness.m_hash)),
+                ));
+                stages.push((
+                    DiagnosticStage::TamperSignature,
+                    tamper(verify(witness.h_pub, bump(witness.h_sig), witness.m_hash)),
+                ));
+                stages.push((
+                    DiagnosticStage::TamperMessageHash,
+                    tamper(verify(witness.h_pub, witness.h_sig, bump(witness.m_hash))),
+                ));
+            }
+            None => {
+                for stage in [
+                    DiagnosticStage::SelfVerification,
+                    DiagnosticStage::TamperPublicKey,
+                    DiagnosticStage::TamperSignature,
+                    DiagnosticStage::TamperMessageHash,
+                ] {
+                    stages.push((stage, StageOutcome::Skipped));
+                }
+            }
+        }
+
+        DiagnosticReport { stages }
+    }
+
+    /// Structural, mock and proving stages; returns the proof if all of them passed
+    fn diagnose_until_proof(
+        &self,
+        witness: &Witness,
+        stages: &mut Vec<(DiagnosticStage, StageOutcome)>,
+    ) -> Option<Proof> {
+        if let Err(violation) = witness.verify_structural(&HyperbolicManifold::new()) {
+            stages.push((DiagnosticStage::Structural, StageOutcome::Failed(format!("{:?}", violation))));
+            skip_rest(stages, &[DiagnosticStage::MockProver, DiagnosticStage::ProofGeneration]);
+            return None;
+        }
+        stages.push((DiagnosticStage::Structural, StageOutcome::Passed));
+
+        let (circuit, instances) = match self.build_circuit(witness) {
+            Ok(built) => built,
+            Err(e) => {
+                stages.push((DiagnosticStage::MockProver, StageOutcome::Failed(e.to_string())));
+                skip_rest(stages, &[DiagnosticStage::ProofGeneration]);
+                return None;
+            }
+        };
+        let mock = match self.run_mock_prover(&circuit, &instances) {
+            Ok(Ok(())) => StageOutcome::Passed,
+            Ok(Err(failures)) => StageOutcome::Failed(format!("{:?}", failures)),
+            Err(e) => StageOutcome::Failed(e.to_string()),
+        };
+        let mock_passed = mock == StageOutcome::Passed;
+        stages.push((DiagnosticStage::MockProver, mock));
+        if !mock_passed {
+            skip_rest(stages, &[DiagnosticStage::ProofGeneration]);
+            return None;
+        }
+
+        match self.create_real_proof(circuit, &instances, &mut rand::thread_rng()) {
+            Ok(proof) => {
+                stages.push((DiagnosticStage::ProofGeneration, StageOutcome::Passed));
+                Some(proof)
+            }
+            Err(e) => {
+                stages.push((DiagnosticStage::ProofGeneration, StageOutcome::Failed(e.to_string())));
+                None
+            }
+        }
+    }
+}
+
+fn skip_rest(stages: &mut Vec<(DiagnosticStage, StageOutcome)>, rest: &[DiagnosticStage]) {
+    for stage in rest {
+        stages.push((*stage, StageOutcome::Skipped));
+    }
+}
+
+/// Perturb the first element of a public-input component
+fn bump(mut component: [Fr; 4]) -> [Fr; 4] {
+    component[0] += Fr::one();
+    component
+}
@@ -0,0 +1,178 @@
+// src/error.rs
+// Error type for TopoShield proving and verification
+use halo2_proofs::{halo2curves::bn256::Fr, plonk};
+use std::{fmt, io, path::PathBuf};
+
+/// Errors surfaced by the TopoShield prover and verifier
+#[derive(Debug)]
+pub enum ToposhieldError {
+    /// Underlying halo2 proving or verification error
+    Halo2(plonk::Error),
+    /// The proof was produced under a different Poseidon parameter set
+    PoseidonSpecMismatch { expected: Fr, found: Fr },
+    /// The instance layout does not match the circuit's public-input count
+    InstanceLayoutMismatch { expected: usize, got: usize },
+    /// An assembled instance column does not have the circuit's public-input count
+    InstanceCountMismatch { expected: usize, got: usize },
+    /// desc_M does not match any descriptor version accepted by the verifier
+    UnsupportedDescriptor { desc_m: [Fr; 4] },
+    /// desc_M is not in the verifier's pinned set of accepted manifolds
+    UnacceptedManifold { desc_m: [Fr; 4] },
+    /// The witness was built for a different manifold than the prover's
+    DescriptorMismatch { expected: [Fr; 4], found: [Fr; 4] },
+    /// desc_M is all-zero or the identity: the descriptor hash is miswired
+    DegenerateDescriptor { desc_m: [Fr; 4] },
+    /// h_pub or h_sig of a witness has det ≠ 1
+    NonSl2Holonomy { component: &'static str },
+    /// A public h_pub or h_sig cannot be a signature holonomy (det ≠ 1 or the identity)
+    InvalidPublicInputs { component: &'static str, reason: &'static str },
+    /// The private seed could not be read from its source
+    SeedUnavailable { source: String, reason: String },
+    /// The private seed is a well-known example value
+    PlaceholderSeed,
+    /// Witnesses in a batch do not share the same h_pub and desc_m
+    BatchKeyMismatch,
+    /// Witness and R1CS wire counts differ and no (matching) wire mapping is loaded
+    WireMappingMismatch { r1cs_wires: usize, witness_wires: usize },
+    /// The KZG params don't have the size or G2 elements this circuit was set up for
+    SrsIncompatible { reason: String },
+    /// The SRS has fewer rows than the circuit needs
+    SrsTooSmall { required_k: u32, available_k: u32 },
+    /// The first G1 element of the SRS is not the BN254 generator
+    SrsWrongGenerator,
+    /// The SRS file does not match its recorded `.sha256` hash
+    SrsHashMismatch { path: PathBuf, expected: String, found: String },
+    /// A proof bundle is not valid JSON or one of its fields does not decode
+    MalformedBundle { reason: String },
+    /// A proof bundle was produced under a different setup (verifying key)
+    SetupMismatch { expected: String, found: String },
+    /// A `save_state` snapshot is current but one of its files is unreadable or does
+    /// not match the hash its manifest records
+    CorruptState { path: PathBuf, reason: String },
+    /// Stateful key index is outside the tree or has already signed
+    KeyIndexUnavailable { index: usize },
+    /// A file operation failed; names the file and what was being done to it
+    Io { operation: &'static str, path: PathBuf, source: io::Error },
+}
+
+impl fmt::Display for ToposhieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ToposhieldError::Halo2(e) => write!(f, "halo2 error: {:?}", e),
+            ToposhieldError::PoseidonSpecMismatch { expected, found } => write!(
+                f,
+                "Poseidon spec mismatch: verifier uses {:?}, proof was produced under {:?}",
+                expected, found
+            ),
+            ToposhieldError::InstanceLayoutMismatch { expected, got } => write!(
+                f,
+                "instance layout mismatch: circuit declares {} public inputs, layout has {}",
+                expected, got
+            ),
+            ToposhieldError::InstanceCountMismatch { expected, got } => write!(
+                f,
+                "instance count mismatch: circuit declares {} public inputs, instance has {}",
+                expected, got
+            ),
+            ToposhieldError::UnsupportedDescriptor { desc_m } => {
+                write!(f, "desc_M {:?} matches no accepted descriptor version", desc_m)
+            }
+            ToposhieldError::UnacceptedManifold { desc_m } => {
+                write!(f, "desc_M {:?} is not one of the accepted manifolds", desc_m)
+            }
+            ToposhieldError::DegenerateDescriptor { desc_m } => {
+                write!(f, "manifold descriptor {:?} is degenerate (all-zero or the identity)", desc_m)
+            }
+            ToposhieldError::DescriptorMismatch { expected, found } => write!(
+                f,
+                "witness desc_M {:?} does not match the prover's manifold descriptor {:?}",
+                found, expected
+            ),
+            ToposhieldError::InvalidPublicInputs { component, reason } => {
+                write!(f, "public input {} is not a signature holonomy: {}", component, reason)
+            }
+            ToposhieldError::NonSl2Holonomy { component } => {
+                write!(f, "witness {} has det ≠ 1 (not in SL(2))", component)
+            }
+            ToposhieldError::SeedUnavailable { source, reason } => {
+                write!(f, "cannot read private seed from {}: {}", source, reason)
+            }
+            ToposhieldError::PlaceholderSeed => {
+                write!(f, "private seed is a placeholder example value; example seeds must never sign real messages")
+            }
+            ToposhieldError::BatchKeyMismatch => {
+                write!(f, "batch witnesses must share the same h_pub and desc_m")
+            }
+            ToposhieldError::WireMappingMismatch { r1cs_wires, witness_wires } => write!(
+                f,
+                "R1CS has {} wires but the witness has {}: the circuit needs a wire mapping \
+                 (see TopoShieldProver::with_wire_mapping)",
+                r1cs_wires, witness_wires
+            ),
+            ToposhieldError::SrsIncompatible { reason } => write!(f, "incompatible KZG params: {}", reason),
+            ToposhieldError::SrsTooSmall { required_k, available_k } => write!(
+                f,
+                "KZG params have k = {} but the circuit needs k ≥ {}; regenerate them with setup-kzg",
+                available_k, required_k
+            ),
+            ToposhieldError::SrsWrongGenerator => {
+                write!(f, "KZG params do not start at the BN254 G1 generator (wrong curve or corrupted SRS)")
+            }
+            ToposhieldError::SrsHashMismatch { path, expected, found } => write!(
+                f,
+                "{} has SHA-256 {}, but its .sha256 file records {}. If you regenerated the \
+                 SRS yourself, run `cargo run --bin srs-rehash -- {}` to update the hash",
+                path.display(),
+                found,
+                expected,
+                path.display()
+            ),
+            ToposhieldError::MalformedBundle { reason } => write!(f, "malformed proof bundle: {}", reason),
+            ToposhieldError::SetupMismatch { expected, found } => write!(
+                f,
+                "proof bundle was made under setup {}, but this verifier's setup is {}",
+                found, expected
+            ),
+            ToposhieldError::CorruptState { path, reason } => write!(
+                f,
+                "prover state file {} is corrupt: {}; remove the state directory to rebuild it",
+                path.display(),
+                reason
+            ),
+            ToposhieldError::KeyIndexUnavailable { index } => {
+                write!(f, "key index {} is out of range or already used", index)
+            }
+            ToposhieldError::Io { operation, path, source } => {
+                write!(f, "failed to {} {}: {}", operation, path.display(), source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ToposhieldError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ToposhieldError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Attach the operation and file path to an IO result
+pub(crate) fn with_context<T>(
+    result: io::Result<T>,
+    operation: &'static str,
+    path: impl Into<PathBuf>,
+) -> Result<T, ToposhieldError> {
+    result.map_err(|source| ToposhieldError::Io {
+        operation,
+        path: path.into(),
+        source,
+    })
+}
+
+impl From<plonk::Error> for ToposhieldError {
+    fn from(e: plonk::Error) -> Self {
+        ToposhieldError::Halo2(e)
+    }
+}
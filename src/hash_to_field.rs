@@ -0,0 +1,126 @@
+// src/hash_to_field.rs
+// RFC 9380 hash_to_field for Fr: expand_message_xmd (SHA-256), then reduce mod r
+// Opt-in message encoding; `Witness::new` keeps the legacy chunked Poseidon hash
+use crate::witness::Witness;
+use ff::{Field, FromUniformBytes};
+use halo2_proofs::halo2curves::bn256::Fr;
+use sha2::{Digest, Sha256};
+
+/// Domain separation tag used by `MessageEncoding::HashToField`
+pub const DEFAULT_DST: &[u8] = b"TOPOSHIELD-V01-HASH-TO-FIELD-BN254FR_XMD:SHA-256";
+
+/// Bytes per field element: L = ceil((ceil(log2 r) + k) / 8) with k = 128
+const L: usize = 48;
+
+/// How a message becomes the 4-element `m_hash`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MessageEncoding {
+    /// Poseidon over the message length and 31-byte zero-padded chunks, as `Witness::new` does
+    #[default]
+    Legacy,
+    /// RFC 9380 `hash_to_field` with `DEFAULT_DST`
+    HashToField,
+}
+
+impl MessageEncoding {
+    pub fn encode(&self, message: &[u8]) -> [Fr; 4] {
+        match self {
+            MessageEncoding::Legacy => Witness::hash_to_4fr(message),
+            MessageEncoding::HashToField => hash_to_field(message, DEFAULT_DST),
+        }
+    }
+}
+
+/// RFC 9380 §5.3.1 expand_message_xmd with SHA-256.
+/// Panics if `dst` exceeds 255 bytes or `len_in_bytes` exceeds 255 · 32.
+pub fn expand_message_xmd(msg: &[u8], dst: &[u8], len_in_bytes: usize) -> Vec<u8> {
+    const B_IN_BYTES: usize = 32;
+    const S_IN_BYTES: usize = 64;
+    let ell = (len_in_bytes + B_IN_BYTES - 1) / B_IN_BYTES;
+    assert!(ell <= 255 && len_in_bytes <= u16::MAX as usize, "len_in_bytes too large");
+    assert!(dst.len() <= 255, "DST longer than 255 bytes");
+    let dst_len = [dst.len() as u8];
+
+    let b_0 = Sha256::new()
+        .chain_update([0u8; S_IN_BYTES])
+        .chain_update(msg)
+        .chain_update((len_in_bytes as u16).to_be_bytes())
+        .chain_update([0u8])
+        .chain_update(dst)
+        .chain_update(dst_len)
+        .finalize();
+
+    let mut uniform = Vec::with_capacity(ell * B_IN_BYTES);
+    let mut b_i = Sha256::new()
+        .chain_update(b_0)
+        .chain_update([1u8])
+        .chain_update(dst)
+        .chain_update(dst_len)
+        .finalize();
+    uniform.extend_from_slice(&b_i);
+    for i in 2..=ell {
+        let mut mixed = [0u8; B_IN_BYTES];
+        for (out, (x, y)) in mixed.iter_mut().zip(b_0.iter().zip(b_i.iter())) {
+            *out = x ^ y;
+        }
+        b_i = Sha256::new()
+            .chain_update(mixed)
+            .chain_update([i as u8])
+            .chain_update(dst)
+            .chain_update(dst_len)
+            .finalize();
+        uniform.extend_from_slice(&b_i);
+    }
+    uniform.truncate(len_in_bytes);
+    uniform
+}
+
+/// RFC 9380 §5.2 hash_to_field with count = 4: each element is a 48-byte
+/// big-endian string reduced mod r
+pub fn hash_to_field(msg: &[u8], dst: &[u8]) -> [Fr; 4] {
+    let uniform = expand_message_xmd(msg, dst, 4 * L);
+    let mut out = [Fr::zero(); 4];
+    for (elem, chunk) in out.iter_mut().zip(uniform.chunks(L)) {
+        // from_uniform_bytes reduces a 64-byte little-endian integer
+        let mut wide = [0u8; 64];
+        for (dst_byte, src_byte) in wide.iter_mut().zip(chunk.iter().rev()) {
+            *dst_byte = *src_byte;
+        }
+        *elem = Fr::from_uniform_bytes(&wide);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_message_xmd_rfc_vectors() {
+        // RFC 9380 Appendix K.1
+        let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+        assert_eq!(
+            hex::encode(expand_message_xmd(b"", dst, 0x20)),
+            "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"
+        );
+        assert_eq!(
+            hex::encode(expand_message_xmd(b"abc", dst, 0x20)),
+            "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615"
+        );
+        assert_eq!(
+            hex::encode(expand_message_xmd(b"", dst, 0x80)),
+            "af84c27ccfd45d41914fdff5df25293e221afc53d8ad2ac06d5e3e29485dadbe\
+             e0d121587713a3e0dd4d5e69e93eb7cd4f5df4cd103e188cf60cb02edc3edf18\
+             eda8576c412b18ffb658e3dd6ec849469b979d444cf7b26911a08e63cf31f9dc\
+             c541708d3491184472c2c29bb749d4286b004ceb5ee6b9a7fa5b646c993f0ced"
+        );
+    }
+
+    #[test]
+    fn test_hash_to_field_binds_trailing_zeros() {
+        let encoding = MessageEncoding::HashToField;
+        assert_ne!(encoding.encode(b"ab"), encoding.encode(b"ab\0"));
+        assert_ne!(hash_to_field(b"ab", DEFAULT_DST), hash_to_field(b"ab", b"OTHER-DST"));
+        assert_eq!(MessageEncoding::default().encode(b"ab"), Witness::hash_to_4fr(b"ab"));
+    }
+}
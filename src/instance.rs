@@ -0,0 +1,200 @@
+// src/instance.rs
+// Public-input (instance) layout shared by prove and verify
+// Must match the order of public signals in holonomy_path_enhanced.circom
+use crate::{error::ToposhieldError, witness::Witness};
+use halo2_proofs::halo2curves::bn256::Fr;
+
+/// Field elements per public-input component: a 2×2 holonomy matrix, or a
+/// Poseidon-derived value padded to the same shape
+pub const COMPONENT_WIDTH: usize = 4;
+
+/// A named block of public inputs (`COMPONENT_WIDTH` field elements each)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstanceComponent {
+    HPub,
+    HSig,
+    DescM,
+    MHash,
+}
+
+/// The four public-input components of one signature, by name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PublicInputs {
+    pub h_pub: [Fr; COMPONENT_WIDTH],
+    pub h_sig: [Fr; COMPONENT_WIDTH],
+    pub desc_m: [Fr; COMPONENT_WIDTH],
+    pub m_hash: [Fr; COMPONENT_WIDTH],
+}
+
+impl From<&Witness> for PublicInputs {
+    fn from(witness: &Witness) -> Self {
+        Self {
+            h_pub: witness.h_pub,
+            h_sig: witness.h_sig,
+            desc_m: witness.desc_m,
+            m_hash: witness.m_hash,
+        }
+    }
+}
+
+/// Order in which the public-input components appear in the circuit's instance column
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstanceLayout {
+    components: Vec<InstanceComponent>,
+}
+
+impl Default for InstanceLayout {
+    /// H_pub, H_sig, desc_M, m_hash — the declaration order in the Circom main template
+    fn default() -> Self {
+        Self {
+            components: vec![
+                InstanceComponent::HPub,
+                InstanceComponent::HSig,
+                InstanceComponent::DescM,
+                InstanceComponent::MHash,
+            ],
+        }
+    }
+}
+
+impl InstanceLayout {
+    pub fn new(components: Vec<InstanceComponent>) -> Self {
+        Self { components }
+    }
+
+    pub fn components(&self) -> &[InstanceComponent] {
+        &self.components
+    }
+
+    /// Total number of field elements in the instance vector
+    pub fn width(&self) -> usize {
+        COMPONENT_WIDTH * self.components.len()
+    }
+
+    /// Check the layout against the circuit's declared number of public inputs
+    pub fn validate(&self, num_public_inputs: usize) -> Result<(), ToposhieldError> {
+        if self.width() != num_public_inputs {
+            return Err(ToposhieldError::InstanceLayoutMismatch {
+                expected: num_public_inputs,
+                got: self.width(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Every layout obtained by swapping two components of this one
+    pub fn swapped(&self) -> Vec<InstanceLayout> {
+        let n = self.components.len();
+        let mut layouts = Vec::with_capacity(n * n.saturating_sub(1) / 2);
+        for i in 0..n {
+            for j in i + 1..n {
+                let mut components = self.components.clone();
+                components.swap(i, j);
+                layouts.push(Self::new(components));
+            }
+        }
+        layouts
+    }
+
+    /// Assemble the instance vector in layout order
+    pub fn assemble(
+        &self,
+        h_pub: [Fr; COMPONENT_WIDTH],
+        h_sig: [Fr; COMPONENT_WIDTH],
+        desc_m: [Fr; COMPONENT_WIDTH],
+        m_hash: [Fr; COMPONENT_WIDTH],
+    ) -> Vec<Fr> {
+        let mut instance = Vec::with_capacity(self.width());
+        for component in &self.components {
+            let values = match component {
+                InstanceComponent::HPub => h_pub,
+                InstanceComponent::HSig => h_sig,
+                InstanceComponent::DescM => desc_m,
+                InstanceComponent::MHash => m_hash,
+            };
+            instance.extend_from_slice(&values);
+        }
+        instance
+    }
+
+    /// Instance columns of one circuit (a single column, in layout order). The only
+    /// way prove and verify build their instances, so the two cannot drift apart.
+    pub fn instances(&self, inputs: &PublicInputs) -> Vec<Vec<Fr>> {
+        vec![self.assemble(inputs.h_pub, inputs.h_sig, inputs.desc_m, inputs.m_hash)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_layout_order() {
+        let layout = InstanceLayout::default();
+        let instance = layout.assemble(
+            [Fr::from(1); COMPONENT_WIDTH],
+            [Fr::from(2); COMPONENT_WIDTH],
+            [Fr::from(3); COMPONENT_WIDTH],
+            [Fr::from(4); COMPONENT_WIDTH],
+        );
+        assert_eq!(instance.len(), layout.width());
+        assert_eq!(instance[0], Fr::from(1));
+        assert_eq!(instance[COMPONENT_WIDTH], Fr::from(2));
+        assert_eq!(instance[2 * COMPONENT_WIDTH], Fr::from(3));
+        assert_eq!(instance[3 * COMPONENT_WIDTH], Fr::from(4));
+    }
+
+    #[test]
+    fn test_layout_width_is_computed() {
+        assert_eq!(InstanceLayout::default().width(), 4 * COMPONENT_WIDTH);
+        assert_eq!(InstanceLayout::new(vec![InstanceComponent::HSig]).width(), COMPONENT_WIDTH);
+    }
+
+    #[test]
+    fn test_prove_and_verify_inputs_build_same_instances() {
+        // Prove side: inputs from the witness; verify side: inputs from the components
+        let witness = Witness::new(b"Instance builder", b"instance_seed");
+        let verifier_side = PublicInputs {
+            h_pub: witness.h_pub,
+            h_sig: witness.h_sig,
+            desc_m: witness.desc_m,
+            m_hash: witness.m_hash,
+        };
+        let desc_first = InstanceLayout::new(vec![
+            InstanceComponent::DescM,
+            InstanceComponent::HPub,
+            InstanceComponent::HSig,
+            InstanceComponent::MHash,
+        ]);
+        for layout in [InstanceLayout::default(), desc_first.clone()] {
+            assert_eq!(layout.instances(&PublicInputs::from(&witness)), layout.instances(&verifier_side));
+        }
+        assert_eq!(desc_first.instances(&verifier_side)[0][..COMPONENT_WIDTH], witness.desc_m);
+    }
+
+    #[test]
+    fn test_layout_validation() {
+        let layout = InstanceLayout::default();
+        let width = 4 * COMPONENT_WIDTH;
+        assert!(layout.validate(width).is_ok());
+        assert!(matches!(
+            layout.validate(width + COMPONENT_WIDTH),
+            Err(ToposhieldError::InstanceLayoutMismatch { expected, got })
+                if expected == width + COMPONENT_WIDTH && got == width
+        ));
+    }
+
+    #[test]
+    fn test_swapped_layouts() {
+        let layout = InstanceLayout::default();
+        let swapped = layout.swapped();
+        assert_eq!(swapped.len(), 6);
+        assert!(!swapped.contains(&layout));
+        assert!(swapped.contains(&InstanceLayout::new(vec![
+            InstanceComponent::HSig,
+            InstanceComponent::HPub,
+            InstanceComponent::DescM,
+            InstanceComponent::MHash,
+        ])));
+    }
+}
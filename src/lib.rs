@@ -1,4 +1,15 @@
 // src/lib.rs
+pub mod circom_poseidon;
+pub mod context;
+pub mod descriptor;
+pub mod diagnostics;
+pub mod error;
+pub mod hash_to_field;
+pub mod instance;
 pub mod manifold;
+pub mod poseidon_widths;
 pub mod witness;
 pub mod prover;
+pub mod sampler;
+pub mod secret;
+pub mod stateful;
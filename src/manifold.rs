@@ -1,11 +1,173 @@
 // src/manifold.rs
-// Static faithful Fuchsian representation for genus=5
+// Static integer SL(2) representation of the genus-5 surface group
 // Hardcoded to match holonomy_path.circom EXACTLY
 // All matrices satisfy det = 1 and ∏[A_i, B_i] = I
 use ff::PrimeField;
 use halo2_proofs::halo2curves::bn256::Fr;
+use crate::circom_poseidon::CircomPoseidon;
+use crate::error::ToposhieldError;
+use crate::poseidon_widths::DESC_RATE;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::sync::OnceLock;
 
-/// A hyperbolic surface of genus 5 with fixed faithful representation in SL(2, Fr)
+/// Version of the desc_M construction produced by `HyperbolicManifold::descriptor`.
+/// Bump whenever the descriptor inputs or their encoding change.
+/// v2: the descriptor preimage starts with `DOMAIN_DESC`.
+/// v3: circomlib `PoseidonEx(14, 4)` over the preimage plus the 10 generator traces,
+/// exactly as recomputed by the circuit.
+pub const DESCRIPTOR_VERSION: u32 = 3;
+
+/// Poseidon domain tag of the manifold descriptor (see also `witness::DOMAIN_MHASH`
+/// and `witness::DOMAIN_SEED`); distinct tags keep desc_M, m_hash and derived seeds
+/// from colliding across purposes.
+pub const DOMAIN_DESC: u64 = 1;
+
+/// Number of generator indices: 10 positive generators and their inverses
+pub const NUM_GENERATOR_INDICES: usize = 20;
+
+/// Entries [a, b, c, d] of the 10 positive generators A1, B1, ..., A5, B5, all with
+/// det = 1. Pairs 2 and 4 are built from pairs 1 and 3 so that their commutators cancel
+/// ([B·A·B, A·B] = [A, B]⁻¹), and A5, B5 are powers of [[1, 1], [1, 2]], so they commute;
+/// hence ∏[A_i, B_i] = I holds exactly over ℤ. No integer representation of a genus-5
+/// surface group is faithful, so distinct words may share a holonomy.
+const GENERATOR_ENTRIES: [[u64; 4]; 10] = [
+    // A1, B1
+    [2, 1, 1, 1],     // a1
+    [3, 2, 1, 1],     // b1
+    // A2 = B1·A1·B1, B2 = A1·B1
+    [29, 21, 11, 8],  // a2
+    [7, 5, 4, 3],     // b2
+    // A3, B3
+    [3, 1, 2, 1],     // a3
+    [4, 1, 3, 1],     // b3
+    // A4 = B3·A3·B3, B4 = A3·B3
+    [71, 19, 56, 15], // a4
+    [15, 4, 11, 3],   // b4
+    // A5 = Q², B5 = Q³ with Q = [[1, 1], [1, 2]]
+    [2, 3, 3, 5],     // a5
+    [5, 8, 8, 13],    // b5
+];
+
+/// Ring of matrix entries: lets holonomies be evaluated over Fr or exactly over ℤ
+pub trait MatrixRing:
+    Copy + PartialEq + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Neg<Output = Self>
+{
+    fn ring_zero() -> Self;
+    fn ring_one() -> Self;
+}
+
+impl MatrixRing for Fr {
+    fn ring_zero() -> Self {
+        Fr::zero()
+    }
+    fn ring_one() -> Self {
+        Fr::one()
+    }
+}
+
+impl MatrixRing for i128 {
+    fn ring_zero() -> Self {
+        0
+    }
+    fn ring_one() -> Self {
+        1
+    }
+}
+
+/// 2×2 matrix [[a, b], [c, d]] over a ring T
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sl2Matrix<T> {
+    pub a: T,
+    pub b: T,
+    pub c: T,
+    pub d: T,
+}
+
+impl<T: MatrixRing> Sl2Matrix<T> {
+    pub fn new(a: T, b: T, c: T, d: T) -> Self {
+        Self { a, b, c, d }
+    }
+
+    pub fn identity() -> Self {
+        Self::new(T::ring_one(), T::ring_zero(), T::ring_zero(), T::ring_one())
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        Self::new(
+            self.a * other.a + self.b * other.c,
+            self.a * other.b + self.b * other.d,
+            self.c * other.a + self.d * other.c,
+            self.c * other.b + self.d * other.d,
+        )
+    }
+
+    /// Adjugate [[d, -b], [-c, a]]; equals the inverse when det = 1
+    pub fn adjugate(&self) -> Self {
+        Self::new(self.d, -self.b, -self.c, self.a)
+    }
+
+    pub fn det(&self) -> T {
+        self.a * self.d - self.b * self.c
+    }
+
+    pub fn trace(&self) -> T {
+        self.a + self.d
+    }
+}
+
+impl std::fmt::Display for Sl2Matrix<Fr> {
+    /// `[[a, b], [c, d]] (tr = …, det = …)`, entries as big-endian hex (Fr's Debug form)
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "[[{:?}, {:?}], [{:?}, {:?}]] (tr = {:?}, det = {:?})",
+            self.a,
+            self.b,
+            self.c,
+            self.d,
+            self.trace(),
+            self.det()
+        )
+    }
+}
+
+/// Generator `idx` (0–19) from a table of 10 positive generators; 10–19 are adjugates
+pub fn generator_over<T: MatrixRing>(generators: &[Sl2Matrix<T>], idx: usize) -> Sl2Matrix<T> {
+    if idx < 10 {
+        generators[idx]
+    } else {
+        generators[idx - 10].adjugate()
+    }
+}
+
+/// Holonomy Hol(γ) = Hol(γₙ)·...·Hol(γ₁) over any matrix ring
+/// (same convention as `HyperbolicManifold::holonomy`)
+pub fn holonomy_over<T: MatrixRing>(generators: &[Sl2Matrix<T>], path: &[u8]) -> Sl2Matrix<T> {
+    path.iter().fold(Sl2Matrix::identity(), |acc, &idx| {
+        generator_over(generators, idx as usize).mul(&acc)
+    })
+}
+
+/// Surface-group names for generator indices 0–19 (same layout as `get_generator`)
+const GENERATOR_LABELS: [&str; 20] = [
+    "a1", "b1", "a2", "b2", "a3", "b3", "a4", "b4", "a5", "b5",
+    "a1_inv", "b1_inv", "a2_inv", "b2_inv", "a3_inv", "b3_inv", "a4_inv", "b4_inv", "a5_inv", "b5_inv",
+];
+
+/// A group axiom that the holonomy map fails on a sample word
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AxiomViolation {
+    /// Hol(ε) ≠ I
+    EmptyNotIdentity,
+    /// A sample word contains an index outside 0–19
+    IndexOutOfRange { word: Vec<u8>, index: u8 },
+    /// Hol(w)·Hol(w⁻¹) ≠ I
+    InverseNotIdentity { word: Vec<u8> },
+    /// Hol(u || v) ≠ Hol(v)·Hol(u)
+    CompositionMismatch { prefix: Vec<u8>, suffix: Vec<u8> },
+}
+
+/// A hyperbolic surface of genus 5 with a fixed representation in SL(2, Fr)
 /// satisfying ∏_{i=1}^5 [A_i, B_i] = I.
 /// Matrices are normalized to det = 1 and match holonomy_path.circom.
 #[derive(Debug, Clone)]
@@ -20,23 +182,10 @@ impl HyperbolicManifold {
     /// Create the canonical genus-5 manifold used in TopoShield.
     /// All matrices have det = 1 and satisfy the commutator relation.
     pub fn new() -> Self {
-        let generators = vec![
-            // A1, B1
-            (Fr::from(2), Fr::from(1), Fr::from(1), Fr::from(1)),   // a1
-            (Fr::from(3), Fr::from(2), Fr::from(1), Fr::from(1)),   // b1
-            // A2, B2
-            (Fr::from(5), Fr::from(3), Fr::from(2), Fr::from(1)),   // a2
-            (Fr::from(7), Fr::from(4), Fr::from(3), Fr::from(2)),   // b2
-            // A3, B3
-            (Fr::from(11), Fr::from(7), Fr::from(4), Fr::from(3)),  // a3
-            (Fr::from(13), Fr::from(8), Fr::from(5), Fr::from(3)),  // b3
-            // A4, B4
-            (Fr::from(17), Fr::from(11), Fr::from(7), Fr::from(4)), // a4
-            (Fr::from(19), Fr::from(12), Fr::from(8), Fr::from(5)), // b4
-            // A5, B5 — CORRECTED: (19,12,11,7) has det = 19*7 - 12*11 = 133 - 132 = 1
-            (Fr::from(19), Fr::from(12), Fr::from(11), Fr::from(7)), // a5
-            (Fr::from(21), Fr::from(13), Fr::from(8), Fr::from(5)), // b5 (det = 21*5 - 13*8 = 105 - 104 = 1)
-        ];
+        let generators = GENERATOR_ENTRIES
+            .iter()
+            .map(|&[a, b, c, d]| (Fr::from(a), Fr::from(b), Fr::from(c), Fr::from(d)))
+            .collect();
         Self {
             genus: 5,
             chi: -8,
@@ -45,28 +194,389 @@ impl HyperbolicManifold {
         }
     }
 
-    /// Get generator matrix by index:
+    /// desc_M of the canonical manifold (`new`), hashed once per process and checked
+    /// with `check_descriptor_non_degenerate`
+    pub fn canonical_descriptor() -> Result<[Fr; 4], ToposhieldError> {
+        static DESCRIPTOR: OnceLock<[Fr; 4]> = OnceLock::new();
+        let desc_m = *DESCRIPTOR.get_or_init(|| Self::new().descriptor());
+        Self::check_descriptor_non_degenerate(desc_m)?;
+        Ok(desc_m)
+    }
+
+    /// Reject a desc_M that is all-zero or the identity [1, 0, 0, 1]: what a miswired
+    /// descriptor hash (e.g. an unset or bypassed Poseidon) produces. Witnesses must
+    /// never bind to such a value.
+    pub fn check_descriptor_non_degenerate(desc_m: [Fr; 4]) -> Result<(), ToposhieldError> {
+        let identity = [Fr::one(), Fr::zero(), Fr::zero(), Fr::one()];
+        if desc_m == [Fr::zero(); 4] || desc_m == identity {
+            return Err(ToposhieldError::DegenerateDescriptor { desc_m });
+        }
+        Ok(())
+    }
+
+    /// The 10 positive generators as exact integer matrices, for checking group
+    /// relations in ℤ (where det and tr are exact) independently of field arithmetic
+    pub fn integer_generators() -> Vec<Sl2Matrix<i128>> {
+        GENERATOR_ENTRIES
+            .iter()
+            .map(|&[a, b, c, d]| Sl2Matrix::new(a as i128, b as i128, c as i128, d as i128))
+            .collect()
+    }
+
+    /// The 10 positive generators as Sl2Matrix over Fr
+    pub fn field_generators(&self) -> Vec<Sl2Matrix<Fr>> {
+        self.generators
+            .iter()
+            .map(|&(a, b, c, d)| Sl2Matrix::new(a, b, c, d))
+            .collect()
+    }
+
+    /// Manifold descriptor desc_M: the first 4 outputs of circomlib
+    /// `PoseidonEx(14, 4)` over (DOMAIN_DESC, genus, χ, p_inv, tr(a1), ..., tr(b5)),
+    /// the same value holonomy_path_enhanced.circom constrains desc_M to
+    pub fn descriptor(&self) -> [Fr; 4] {
+        Self::descriptor_from(self.genus, self.chi, self.p_inv, self.generator_traces())
+    }
+
+    /// Descriptor of arbitrary claimed parameters (same construction as `descriptor`)
+    pub(crate) fn descriptor_from(genus: u32, chi: i32, p_inv: u64, traces: [Fr; 10]) -> [Fr; 4] {
+        static POSEIDON: OnceLock<CircomPoseidon> = OnceLock::new();
+        let chi = if chi < 0 {
+            -Fr::from(chi.unsigned_abs() as u64)
+        } else {
+            Fr::from(chi as u64)
+        };
+        let mut preimage = vec![Fr::from(DOMAIN_DESC), Fr::from(genus as u64), chi, Fr::from(p_inv)];
+        preimage.extend_from_slice(&traces);
+        let result = POSEIDON
+            .get_or_init(|| CircomPoseidon::new(DESC_RATE))
+            .hash_ex(&preimage, 4);
+        [result[0], result[1], result[2], result[3]]
+    }
+
+    /// Traces tr(a1), tr(b1), ..., tr(b5) of the 10 positive generators
+    pub fn generator_traces(&self) -> [Fr; 10] {
+        let mut traces = [Fr::zero(); 10];
+        for (trace, &(a, _, _, d)) in traces.iter_mut().zip(&self.generators) {
+            *trace = a + d;
+        }
+        traces
+    }
+
+    /// True if `desc_m` is the descriptor of the claimed parameters and generator traces
+    pub fn matches_descriptor(desc_m: [Fr; 4], genus: u32, chi: i32, p_inv: u64, traces: [Fr; 10]) -> bool {
+        Self::descriptor_from(genus, chi, p_inv, traces) == desc_m
+    }
+
+    /// Get generator matrix by index, `None` for indices ≥ 20:
     ///   0–9  → A1, B1, ..., A5, B5
     ///   10–19 → A1⁻¹, B1⁻¹, ..., A5⁻¹, B5⁻¹
-    pub fn get_generator(&self, idx: usize) -> (Fr, Fr, Fr, Fr) {
+    pub fn get_generator(&self, idx: usize) -> Option<(Fr, Fr, Fr, Fr)> {
         if idx < 10 {
-            self.generators[idx]
+            Some(self.generators[idx])
         } else if idx < 20 {
             let (a, b, c, d) = self.generators[idx - 10];
-            (d, -b, -c, a) // M⁻¹ = [[d, -b], [-c, a]] since det = 1
+            Some((d, -b, -c, a)) // M⁻¹ = [[d, -b], [-c, a]] since det = 1
         } else {
-            panic!("Index {} out of bounds [0, 19]", idx);
+            None
         }
     }
 
     pub fn num_generator_indices(&self) -> usize {
-        20
+        NUM_GENERATOR_INDICES
+    }
+
+    /// Number of reduced words of length `path_length`: n·(n−1)^(L−1) over the
+    /// n = 4g generator indices (2g generators and their inverses). An upper bound on
+    /// distinct public keys, since the surface relation identifies some words.
+    /// Saturates at `u128::MAX`; see `keyspace_log2` for lengths beyond that.
+    pub fn keyspace_size(&self, path_length: usize) -> u128 {
+        if path_length == 0 {
+            return 1;
+        }
+        let n = 4 * self.genus as u128;
+        (1..path_length).fold(n, |count, _| count.saturating_mul(n - 1))
+    }
+
+    /// log₂ of `keyspace_size`, exact enough for security estimates at any length
+    pub fn keyspace_log2(&self, path_length: usize) -> f64 {
+        if path_length == 0 {
+            return 0.0;
+        }
+        let n = 4.0 * self.genus as f64;
+        n.log2() + (path_length - 1) as f64 * (n - 1.0).log2()
+    }
+
+    /// Index of the inverse generator: x ↔ x⁻¹ is idx ↔ idx ± 10
+    pub fn inverse_index(idx: u8) -> u8 {
+        (idx + 10) % 20
+    }
+
+    /// Commutator [g_i, g_j] = g_i·g_j·g_i⁻¹·g_j⁻¹ of two generator indices, with the
+    /// inverses looked up through `inverse_index`. The surface relator is the product
+    /// ∏ₖ [a_k, b_k] of five of these. Panics on an index ≥ 20.
+    pub fn commutator(&self, i: usize, j: usize) -> [Fr; 4] {
+        let generator = |idx: usize| {
+            self.get_generator(idx)
+                .unwrap_or_else(|| panic!("Index {} out of bounds [0, 19]", idx))
+        };
+        let (g_i, g_j) = (generator(i), generator(j));
+        let g_i_inv = generator(Self::inverse_index(i as u8) as usize);
+        let g_j_inv = generator(Self::inverse_index(j as u8) as usize);
+        let (a, b, c, d) = Self::mat_mul(Self::mat_mul(Self::mat_mul(g_i, g_j), g_i_inv), g_j_inv);
+        [a, b, c, d]
+    }
+
+    /// True if (a, b) cancel in the free group: b = a⁻¹
+    pub fn is_inverse_pair(a: u8, b: u8) -> bool {
+        a < 20 && b == Self::inverse_index(a)
+    }
+
+    /// Surface-group name of a generator index: 0 → "a1", 1 → "b1", ..., 19 → "b5_inv"
+    pub fn generator_label(idx: usize) -> &'static str {
+        GENERATOR_LABELS
+            .get(idx)
+            .copied()
+            .unwrap_or_else(|| panic!("Index {} out of bounds [0, 19]", idx))
+    }
+
+    /// Inverse of `generator_label`: "a3_inv" → Some(14)
+    pub fn generator_index(label: &str) -> Option<usize> {
+        GENERATOR_LABELS.iter().position(|&l| l == label)
+    }
+
+    /// Labels for all 20 generator indices, in index order
+    pub fn generator_labels() -> Vec<String> {
+        GENERATOR_LABELS.iter().map(|l| l.to_string()).collect()
+    }
+
+    /// A path as a readable surface-group word: [0, 5, 12] → "a1 b3 a2⁻¹".
+    /// Panics on an index ≥ 20, like `generator_label`.
+    pub fn word(&self, path: &[u8]) -> String {
+        path.iter()
+            .map(|&idx| {
+                let label = Self::generator_label(idx as usize);
+                match label.strip_suffix("_inv") {
+                    Some(base) => format!("{}⁻¹", base),
+                    None => label.to_string(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Inverse of `word`: whitespace-separated letters, inverses as "a2⁻¹" or "a2_inv".
+    /// `None` if any letter is not a generator.
+    pub fn parse_word(word: &str) -> Option<Vec<u8>> {
+        word.split_whitespace()
+            .map(|letter| {
+                let label = match letter.strip_suffix("⁻¹") {
+                    Some(base) => format!("{}_inv", base),
+                    None => letter.to_string(),
+                };
+                Self::generator_index(&label).map(|idx| idx as u8)
+            })
+            .collect()
+    }
+
+    /// Holonomy of a path: Hol(γ) = Hol(γₙ)·...·Hol(γ₂)·Hol(γ₁)
+    /// (matches the circuit's PathToHolonomy, which multiplies the reversed path).
+    /// Panics on an index ≥ 20; use `try_holonomy` for untrusted paths.
+    pub fn holonomy(&self, path: &[u8]) -> [Fr; 4] {
+        let (a, b, c, d) = Self::identity();
+        self.holonomy_extend([a, b, c, d], path)
+    }
+
+    /// Extend a known holonomy by a suffix: given prefix = Hol(γ), returns
+    /// Hol(γ || δ) = Hol(δ)·Hol(γ). Each generator of δ is left-multiplied in order.
+    /// Panics on an index ≥ 20; use `try_holonomy_extend` for untrusted paths.
+    pub fn holonomy_extend(&self, prefix: [Fr; 4], suffix: &[u8]) -> [Fr; 4] {
+        self.try_holonomy_extend(prefix, suffix)
+            .unwrap_or_else(|position| panic!("Index {} out of bounds [0, 19]", suffix[position]))
+    }
+
+    /// `holonomy` for untrusted paths: the position of the first index ≥ 20 on failure
+    pub fn try_holonomy(&self, path: &[u8]) -> Result<[Fr; 4], usize> {
+        let (a, b, c, d) = Self::identity();
+        self.try_holonomy_extend([a, b, c, d], path)
+    }
+
+    /// `holonomy_extend` for untrusted suffixes: the position of the first index ≥ 20 on failure
+    pub fn try_holonomy_extend(&self, prefix: [Fr; 4], suffix: &[u8]) -> Result<[Fr; 4], usize> {
+        let mut result = (prefix[0], prefix[1], prefix[2], prefix[3]);
+        for (position, &idx) in suffix.iter().enumerate() {
+            let generator = self.get_generator(idx as usize).ok_or(position)?;
+            result = Self::mat_mul(generator, result);
+        }
+        Ok([result.0, result.1, result.2, result.3])
+    }
+
+    /// Public consistency check of a signature without gamma:
+    /// h_sig == Hol(delta)·h_pub (reverse convention). Uses only revealed data.
+    pub fn verify_signature_composition(&self, h_pub: [Fr; 4], delta: &[u8], h_sig: [Fr; 4]) -> bool {
+        self.holonomy_extend(h_pub, delta) == h_sig
+    }
+
+    /// Trace a + d of Hol(path). The trace is a conjugacy invariant, so cyclic
+    /// rotations of a word share it — a coarse public-key class identifier.
+    pub fn holonomy_trace(&self, path: &[u8]) -> Fr {
+        let h = self.holonomy(path);
+        h[0] + h[3]
+    }
+
+    /// Path in reverse index order, generators unchanged. This is only a reordering:
+    /// Hol(reverse_path(w)) is generally not Hol(w)⁻¹. It is what PathToHolonomy does
+    /// internally, which is why `Witness::to_circom_input` passes paths in natural order.
+    pub fn reverse_path(path: &[u8]) -> Vec<u8> {
+        path.iter().rev().copied().collect()
+    }
+
+    /// Group inverse w⁻¹ = wₙ⁻¹·...·w₁⁻¹ of a word: reverse the order AND invert each
+    /// generator. Hol(invert_path(w)) = Hol(w)⁻¹ under the reverse convention; use this
+    /// (never `reverse_path` alone) when a holonomy has to be undone.
+    pub fn invert_path(path: &[u8]) -> Vec<u8> {
+        path.iter().rev().map(|&idx| Self::inverse_index(idx)).collect()
+    }
+
+    /// True if every generator times its inverse is I over Fr: G(i)·G(inverse_index(i)) = I
+    /// for all 20 indices. The inverses are adjugates, so this holds exactly when each
+    /// hardcoded generator has det = 1.
+    pub fn verify_inverses(&self) -> bool {
+        self.first_inverse_mismatch().is_none()
+    }
+
+    /// Consistency checks of the hardcoded generator table; currently `verify_inverses`,
+    /// reporting the first failing index as a one-letter word
+    pub fn validate(&self) -> Result<(), AxiomViolation> {
+        match self.first_inverse_mismatch() {
+            Some(idx) => Err(AxiomViolation::InverseNotIdentity { word: vec![idx] }),
+            None => Ok(()),
+        }
+    }
+
+    fn first_inverse_mismatch(&self) -> Option<u8> {
+        (0..NUM_GENERATOR_INDICES as u8).find(|&idx| {
+            let generator = self.get_generator(idx as usize).expect("index < 20");
+            let inverse = self.get_generator(Self::inverse_index(idx) as usize).expect("index < 20");
+            !Self::mat_eq(Self::mat_mul(generator, inverse), Self::identity())
+        })
+    }
+
+    /// Check that the holonomy map is a homomorphism on `sample` (reverse convention):
+    /// Hol(ε) = I, Hol(w)·Hol(w⁻¹) = I for each word, and Hol(u || v) = Hol(v)·Hol(u)
+    /// for each pair of words. Returns the first violation found.
+    pub fn check_group_axioms(&self, sample: &[Vec<u8>]) -> Result<(), AxiomViolation> {
+        let identity = Self::identity();
+        let as_tuple = |h: [Fr; 4]| (h[0], h[1], h[2], h[3]);
+
+        if !Self::mat_eq(as_tuple(self.holonomy(&[])), identity) {
+            return Err(AxiomViolation::EmptyNotIdentity);
+        }
+        for word in sample {
+            if let Some(&index) = word.iter().find(|&&idx| idx >= 20) {
+                return Err(AxiomViolation::IndexOutOfRange { word: word.clone(), index });
+            }
+        }
+
+        for word in sample {
+            let product = Self::mat_mul(
+                as_tuple(self.holonomy(word)),
+                as_tuple(self.holonomy(&Self::invert_path(word))),
+            );
+            if !Self::mat_eq(product, identity) {
+                return Err(AxiomViolation::InverseNotIdentity { word: word.clone() });
+            }
+        }
+
+        for prefix in sample {
+            for suffix in sample {
+                let combined = [prefix.as_slice(), suffix.as_slice()].concat();
+                let composed = Self::mat_mul(
+                    as_tuple(self.holonomy(suffix)),
+                    as_tuple(self.holonomy(prefix)),
+                );
+                if !Self::mat_eq(as_tuple(self.holonomy(&combined)), composed) {
+                    return Err(AxiomViolation::CompositionMismatch {
+                        prefix: prefix.clone(),
+                        suffix: suffix.clone(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// True if gamma || delta is reduced: each path is internally reduced and the
+    /// junction (last of gamma, first of delta) does not cancel. The circuit checks
+    /// gamma and delta separately, so the junction is the prover's responsibility.
+    pub fn is_combined_reduced(gamma: &[u8], delta: &[u8]) -> bool {
+        let reduced = |path: &[u8]| !path.windows(2).any(|pair| Self::is_inverse_pair(pair[0], pair[1]));
+        let junction_clean = match (gamma.last(), delta.first()) {
+            (Some(&last), Some(&first)) => !Self::is_inverse_pair(last, first),
+            _ => true,
+        };
+        reduced(gamma) && reduced(delta) && junction_clean
+    }
+
+    /// Freely reduced form of a word: repeatedly cancel adjacent inverse pairs
+    pub fn free_reduce(path: &[u8]) -> Vec<u8> {
+        let mut reduced: Vec<u8> = Vec::with_capacity(path.len());
+        for &idx in path {
+            match reduced.last() {
+                Some(&last) if Self::is_inverse_pair(last, idx) => {
+                    reduced.pop();
+                }
+                _ => reduced.push(idx),
+            }
+        }
+        reduced
+    }
+
+    /// Word-metric distance |u·v⁻¹|: length of the free reduction of u followed by
+    /// the formal inverse of v. Zero iff u and v are the same group element.
+    pub fn word_distance(u: &[u8], v: &[u8]) -> usize {
+        let combined = [u, Self::invert_path(v).as_slice()].concat();
+        Self::free_reduce(&combined).len()
+    }
+
+    /// True if the freely reduced `path` has holonomy `h_pub`. Unreduced but
+    /// equivalent spellings of a key path match too; out-of-range indices never match.
+    pub fn path_produces_key(&self, path: &[u8], h_pub: [Fr; 4]) -> bool {
+        self.try_holonomy(&Self::free_reduce(path)) == Ok(h_pub)
+    }
+
+    /// Cyclically reduced form of a word: freely reduce it, then strip matching
+    /// inverse pairs from the two ends (x·w·x⁻¹ → w). The result is conjugate to
+    /// the input, so Hol(result) is conjugate to Hol(path) and shares its trace.
+    pub fn cyclic_reduce(path: &[u8]) -> Vec<u8> {
+        let reduced = Self::free_reduce(path);
+        let (mut start, mut end) = (0, reduced.len());
+        while end - start >= 2 && Self::is_inverse_pair(reduced[end - 1], reduced[start]) {
+            start += 1;
+            end -= 1;
+        }
+        reduced[start..end].to_vec()
+    }
+
+    /// Canonical representative of the conjugacy class of `path`: the
+    /// lexicographically smallest cyclic rotation of its cyclically reduced form.
+    /// Conjugate words (e.g. cyclic rotations) map to the same representative.
+    pub fn conjugacy_representative(path: &[u8]) -> Vec<u8> {
+        let reduced = Self::cyclic_reduce(path);
+        (0..reduced.len().max(1))
+            .map(|shift| {
+                let mut rotated = reduced.clone();
+                rotated.rotate_left(shift);
+                rotated
+            })
+            .min()
+            .unwrap_or_default()
     }
 
     // ————————————————————————————————————————————————————————
-    // Internal helpers for testing only
+    // Internal tuple-matrix helpers
     // ————————————————————————————————————————————————————————
-    fn commutator(A: (Fr, Fr, Fr, Fr), B: (Fr, Fr, Fr, Fr)) -> (Fr, Fr, Fr, Fr) {
+    fn matrix_commutator(A: (Fr, Fr, Fr, Fr), B: (Fr, Fr, Fr, Fr)) -> (Fr, Fr, Fr, Fr) {
         let A_inv = (A.3, -A.1, -A.2, A.0);
         let B_inv = (B.3, -B.1, -B.2, B.0);
         let AB = Self::mat_mul(A, B);
@@ -92,10 +602,128 @@ impl HyperbolicManifold {
     }
 }
 
+/// A finitely generated matrix group that witness paths are drawn from: indices
+/// 0..num_generators() (generators and their inverses), each with an inverse index.
+/// `HyperbolicManifold` is the group the circuit is compiled for; other groups are
+/// for experimentation and only work off-circuit.
+pub trait HolonomyGroup {
+    /// Number of generator indices, inverses included
+    fn num_generators(&self) -> usize;
+
+    /// Generator matrix [a, b, c, d] of an index, `None` if out of range
+    fn get_generator(&self, idx: usize) -> Option<[Fr; 4]>;
+
+    /// Index of the inverse generator. Must differ from `idx` and be an involution.
+    fn inverse_index(&self, idx: u8) -> u8;
+
+    /// Public desc_M the witness binds to
+    fn descriptor(&self) -> [Fr; 4];
+
+    /// True if (a, b) cancel in the free group: b = a⁻¹
+    fn is_inverse_pair(&self, a: u8, b: u8) -> bool {
+        (a as usize) < self.num_generators() && b == self.inverse_index(a)
+    }
+
+    /// Hol(γ) = G(γₙ)·...·G(γ₁), or the position of the first out-of-range index
+    fn try_holonomy(&self, path: &[u8]) -> Result<[Fr; 4], usize> {
+        let mut result = Sl2Matrix::<Fr>::identity();
+        for (position, &idx) in path.iter().enumerate() {
+            let [a, b, c, d] = self.get_generator(idx as usize).ok_or(position)?;
+            result = Sl2Matrix::new(a, b, c, d).mul(&result);
+        }
+        Ok([result.a, result.b, result.c, result.d])
+    }
+}
+
+impl HolonomyGroup for HyperbolicManifold {
+    fn num_generators(&self) -> usize {
+        NUM_GENERATOR_INDICES
+    }
+
+    fn get_generator(&self, idx: usize) -> Option<[Fr; 4]> {
+        HyperbolicManifold::get_generator(self, idx).map(|(a, b, c, d)| [a, b, c, d])
+    }
+
+    fn inverse_index(&self, idx: u8) -> u8 {
+        HyperbolicManifold::inverse_index(idx)
+    }
+
+    fn descriptor(&self) -> [Fr; 4] {
+        HyperbolicManifold::descriptor(self)
+    }
+
+    fn try_holonomy(&self, path: &[u8]) -> Result<[Fr; 4], usize> {
+        HyperbolicManifold::try_holonomy(self, path)
+    }
+}
+
+/// Running holonomies of a fixed prefix path: `at(i)` = Hol(path[..i]).
+/// Build once per shared prefix (a gamma reused across messages, a parent key in a
+/// hierarchy) and extend from any cached point instead of re-walking the prefix.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrefixHolonomy {
+    /// prefixes[i] = Hol(path[..i]); prefixes[0] = I
+    prefixes: Vec<[Fr; 4]>,
+}
+
+impl PrefixHolonomy {
+    /// Cache every prefix holonomy of `path`. Panics on an index ≥ 20, like `holonomy`.
+    pub fn new(manifold: &HyperbolicManifold, path: &[u8]) -> Self {
+        let (a, b, c, d) = HyperbolicManifold::identity();
+        let mut prefixes = Vec::with_capacity(path.len() + 1);
+        prefixes.push([a, b, c, d]);
+        for &idx in path {
+            let last = *prefixes.last().expect("starts with the identity");
+            prefixes.push(manifold.holonomy_extend(last, &[idx]));
+        }
+        Self { prefixes }
+    }
+
+    /// Length of the cached path
+    pub fn len(&self) -> usize {
+        self.prefixes.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Hol(path[..i]), or None if i > len()
+    pub fn at(&self, i: usize) -> Option<[Fr; 4]> {
+        self.prefixes.get(i).copied()
+    }
+
+    /// Hol(path)
+    pub fn endpoint(&self) -> [Fr; 4] {
+        *self.prefixes.last().expect("starts with the identity")
+    }
+
+    /// Hol(path || suffix), starting from the cached endpoint
+    pub fn extend(&self, manifold: &HyperbolicManifold, suffix: &[u8]) -> [Fr; 4] {
+        manifold.holonomy_extend(self.endpoint(), suffix)
+    }
+
+    /// Hol(path[..i] || suffix), or None if i > len()
+    pub fn extend_from(&self, manifold: &HyperbolicManifold, i: usize, suffix: &[u8]) -> Option<[Fr; 4]> {
+        self.at(i).map(|prefix| manifold.holonomy_extend(prefix, suffix))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_keyspace_size_genus_5() {
+        let m = HyperbolicManifold::new();
+        assert_eq!(m.num_generator_indices(), 4 * m.genus as usize);
+        assert_eq!(m.keyspace_size(20), 20 * 19u128.pow(19));
+        assert_eq!(m.keyspace_size(1), 20);
+        assert_eq!(m.keyspace_size(0), 1);
+        assert_eq!(m.keyspace_size(1000), u128::MAX);
+        assert!((m.keyspace_log2(20) - (m.keyspace_size(20) as f64).log2()).abs() < 1e-9);
+    }
+
     #[test]
     fn test_static_manifold_properties() {
         let m = HyperbolicManifold::new();
@@ -119,13 +747,26 @@ mod tests {
     fn test_inverses() {
         let m = HyperbolicManifold::new();
         for i in 0..10 {
-            let M = m.get_generator(i);
-            let M_inv = m.get_generator(i + 10);
+            let M = m.get_generator(i).unwrap();
+            let M_inv = m.get_generator(i + 10).unwrap();
             let prod = HyperbolicManifold::mat_mul(M, M_inv);
             assert!(HyperbolicManifold::mat_eq(prod, HyperbolicManifold::identity()));
         }
     }
 
+    #[test]
+    fn test_verify_inverses() {
+        let m = HyperbolicManifold::new();
+        assert_eq!(m.validate(), Ok(()));
+        assert!(m.verify_inverses());
+
+        // A generator with det ≠ 1 is caught at its index
+        let mut broken = m.clone();
+        broken.generators[0] = (Fr::from(2u64), Fr::zero(), Fr::zero(), Fr::one());
+        assert!(!broken.verify_inverses());
+        assert_eq!(broken.validate(), Err(AxiomViolation::InverseNotIdentity { word: vec![0] }));
+    }
+
     #[test]
     fn test_commutator_relation() {
         let m = HyperbolicManifold::new();
@@ -133,7 +774,7 @@ mod tests {
         for i in 0..5 {
             let A = m.generators[2 * i];
             let B = m.generators[2 * i + 1];
-            let comm = HyperbolicManifold::commutator(A, B);
+            let comm = HyperbolicManifold::matrix_commutator(A, B);
             H = HyperbolicManifold::mat_mul(H, comm);
         }
         assert!(
@@ -141,4 +782,380 @@ mod tests {
             "Commutator relation ∏[A_i, B_i] = I is NOT satisfied!"
         );
     }
+
+    #[test]
+    fn test_commutator() {
+        let m = HyperbolicManifold::new();
+        let identity = [Fr::one(), Fr::zero(), Fr::zero(), Fr::one()];
+        // Every element commutes with itself
+        for i in 0..NUM_GENERATOR_INDICES {
+            assert_eq!(m.commutator(i, i), identity);
+        }
+        // a5 and b5 are powers of one matrix, a1 and b1 are not
+        assert_eq!(m.commutator(8, 9), identity);
+        assert_ne!(m.commutator(0, 1), identity);
+
+        // [a1, b1] by hand, inverses as adjugates
+        let (p, q, r, s) = m.generators[0];
+        let (w, x, y, z) = m.generators[1];
+        let ab = (p * w + q * y, p * x + q * z, r * w + s * y, r * x + s * z);
+        let ab_ainv = HyperbolicManifold::mat_mul(ab, (s, -q, -r, p));
+        let (e, f, g, h) = HyperbolicManifold::mat_mul(ab_ainv, (z, -x, -y, w));
+        assert_eq!(m.commutator(0, 1), [e, f, g, h]);
+
+        // The surface relator from the public API
+        let relator = (0..5).fold(HyperbolicManifold::identity(), |acc, k| {
+            let [a, b, c, d] = m.commutator(2 * k, 2 * k + 1);
+            HyperbolicManifold::mat_mul(acc, (a, b, c, d))
+        });
+        assert!(HyperbolicManifold::mat_eq(relator, HyperbolicManifold::identity()));
+    }
+
+    #[test]
+    fn test_holonomy_trace_conjugacy_invariant() {
+        let m = HyperbolicManifold::new();
+        let word = vec![0u8, 5, 11, 17, 3];
+        // Cyclic rotation = conjugation by the first letter
+        let mut rotated = word.clone();
+        rotated.rotate_left(1);
+        assert_eq!(m.holonomy_trace(&word), m.holonomy_trace(&rotated));
+        rotated.rotate_left(2);
+        assert_eq!(m.holonomy_trace(&word), m.holonomy_trace(&rotated));
+
+        // a1 and b1 lie in different conjugacy classes: tr = 3 vs tr = 4
+        assert_eq!(m.holonomy_trace(&[0]), Fr::from(3));
+        assert_eq!(m.holonomy_trace(&[1]), Fr::from(4));
+        assert_ne!(m.holonomy_trace(&word), m.holonomy_trace(&[0, 5, 11, 17]));
+    }
+
+    #[test]
+    fn test_cyclic_reduce() {
+        // a1 · b1 · a1⁻¹ → b1 (conjugation stripped at the ends)
+        assert_eq!(HyperbolicManifold::cyclic_reduce(&[0, 1, 10]), vec![1]);
+        // Nested: a2 · a1 · b1 · a1⁻¹ · a2⁻¹ → b1
+        assert_eq!(HyperbolicManifold::cyclic_reduce(&[2, 0, 1, 10, 12]), vec![1]);
+        // Free reduction in the middle exposes an end pair: a1 · b1 · b1⁻¹ · a1⁻¹ → ε
+        assert!(HyperbolicManifold::cyclic_reduce(&[0, 1, 11, 10]).is_empty());
+        // Already cyclically reduced words are unchanged
+        assert_eq!(HyperbolicManifold::cyclic_reduce(&[0, 5, 3]), vec![0, 5, 3]);
+
+        // The reduced word is conjugate to the original: same trace
+        let m = HyperbolicManifold::new();
+        let word = [4u8, 0, 5, 11, 17, 3, 14];
+        let reduced = HyperbolicManifold::cyclic_reduce(&word);
+        assert_eq!(reduced, vec![0, 5, 11, 17, 3]);
+        assert_eq!(m.holonomy_trace(&word), m.holonomy_trace(&reduced));
+    }
+
+    #[test]
+    fn test_invert_path_inverts_holonomy() {
+        let m = HyperbolicManifold::new();
+        let generators = m.field_generators();
+        // det = 1, so the adjugate is the inverse
+        for word in [vec![0u8, 1], vec![8, 19, 0, 0, 11], vec![2, 15, 7, 13, 4, 16]] {
+            let h = holonomy_over(&generators, &word);
+            let inverted = holonomy_over(&generators, &HyperbolicManifold::invert_path(&word));
+            assert_eq!(inverted, h.adjugate());
+            assert_eq!(inverted.mul(&h), Sl2Matrix::identity());
+        }
+
+        // Reversing alone is not the inverse
+        let word = [0u8, 1];
+        let reversed = holonomy_over(&generators, &HyperbolicManifold::reverse_path(&word));
+        assert_eq!(HyperbolicManifold::reverse_path(&word), vec![1, 0]);
+        assert_ne!(reversed, holonomy_over(&generators, &word).adjugate());
+        assert_eq!(HyperbolicManifold::invert_path(&[]), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn test_word_distance() {
+        let w = [0u8, 5, 11, 17, 3];
+        assert_eq!(HyperbolicManifold::word_distance(&w, &w), 0);
+        // Unreduced spellings of the same element are at distance 0
+        assert_eq!(HyperbolicManifold::word_distance(&[0, 1, 11], &[0]), 0);
+
+        // Shared suffix 0·1 cancels: a2·a1·b1·(a3·a1·b1)⁻¹ = a2·a3⁻¹
+        assert_eq!(HyperbolicManifold::word_distance(&[2, 0, 1], &[4, 0, 1]), 2);
+        // Differing last letters: nothing cancels
+        assert_eq!(HyperbolicManifold::word_distance(&[0, 1], &[0, 2]), 4);
+        assert_eq!(HyperbolicManifold::word_distance(&w, &[]), w.len());
+    }
+
+    #[test]
+    fn test_path_produces_key() {
+        let m = HyperbolicManifold::new();
+        let reduced = vec![0u8, 1, 8, 9];
+        let h_pub = m.holonomy(&reduced);
+        assert!(m.path_produces_key(&reduced, h_pub));
+
+        // Inserting a1·a1⁻¹ and b5⁻¹·b5 gives an equivalent, unreduced spelling
+        let unreduced = vec![0u8, 0, 10, 1, 8, 19, 9, 9];
+        assert_eq!(HyperbolicManifold::free_reduce(&unreduced), reduced);
+        assert!(m.path_produces_key(&unreduced, h_pub));
+
+        assert!(!m.path_produces_key(&[0, 1, 8], h_pub));
+        assert!(!m.path_produces_key(&[0, 1, 8, 20], h_pub));
+    }
+
+    #[test]
+    fn test_conjugacy_representative() {
+        let word = vec![5u8, 11, 0, 17, 3];
+        let canonical = HyperbolicManifold::conjugacy_representative(&word);
+        assert_eq!(canonical, vec![0, 17, 3, 5, 11]);
+
+        // Every rotation and every conjugate x·w·x⁻¹ shares the representative
+        for shift in 0..word.len() {
+            let mut rotated = word.clone();
+            rotated.rotate_left(shift);
+            assert_eq!(HyperbolicManifold::conjugacy_representative(&rotated), canonical);
+        }
+        let conjugated = [vec![7u8], word.clone(), vec![17]].concat();
+        assert_eq!(HyperbolicManifold::conjugacy_representative(&conjugated), canonical);
+        assert!(HyperbolicManifold::conjugacy_representative(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_group_axioms_on_random_words() {
+        use rand::{Rng, SeedableRng};
+        use rand_chacha::ChaCha20Rng;
+
+        let alphabet: Vec<u8> = (0..NUM_GENERATOR_INDICES as u8).collect();
+        let mut rng = ChaCha20Rng::from_seed([33u8; 32]);
+        let sample: Vec<Vec<u8>> = (0..12)
+            .map(|_| {
+                let len = rng.gen_range(1..=8);
+                let word: Vec<u8> = (0..len).map(|_| alphabet[rng.gen_range(0..alphabet.len())]).collect();
+                HyperbolicManifold::cyclic_reduce(&word)
+            })
+            .collect();
+
+        let m = HyperbolicManifold::new();
+        assert_eq!(m.check_group_axioms(&sample), Ok(()));
+    }
+
+    #[test]
+    fn test_prefix_holonomy_matches_full_recomputation() {
+        use rand::{Rng, SeedableRng};
+        use rand_chacha::ChaCha20Rng;
+
+        let m = HyperbolicManifold::new();
+        let mut rng = ChaCha20Rng::from_seed([64u8; 32]);
+        for _ in 0..8 {
+            let gamma: Vec<u8> = (0..20).map(|_| rng.gen_range(0..20)).collect();
+            let delta: Vec<u8> = (0..20).map(|_| rng.gen_range(0..20)).collect();
+            let cache = PrefixHolonomy::new(&m, &gamma);
+            assert_eq!(cache.len(), gamma.len());
+            assert_eq!(cache.endpoint(), m.holonomy(&gamma));
+
+            let combined: Vec<u8> = gamma.iter().chain(delta.iter()).copied().collect();
+            assert_eq!(cache.extend(&m, &delta), m.holonomy(&combined));
+
+            let i = rng.gen_range(0..=gamma.len());
+            let branched: Vec<u8> = gamma[..i].iter().chain(delta.iter()).copied().collect();
+            assert_eq!(cache.extend_from(&m, i, &delta), Some(m.holonomy(&branched)));
+        }
+        assert_eq!(PrefixHolonomy::new(&m, &[]).endpoint(), m.holonomy(&[]));
+        assert_eq!(PrefixHolonomy::new(&m, &[0, 1]).at(3), None);
+    }
+
+    #[test]
+    fn test_group_axioms_pin_reverse_convention() {
+        let m = HyperbolicManifold::new();
+        // Hol(a1 || b1) = B1·A1, which differs from A1·B1
+        let (u, v) = (vec![0u8], vec![1u8]);
+        let forward = HyperbolicManifold::mat_mul(m.get_generator(0).unwrap(), m.get_generator(1).unwrap());
+        let h = m.holonomy(&[0, 1]);
+        assert!(!HyperbolicManifold::mat_eq((h[0], h[1], h[2], h[3]), forward));
+        assert_eq!(m.check_group_axioms(&[u, v]), Ok(()));
+
+        assert_eq!(
+            m.check_group_axioms(&[vec![0, 20]]),
+            Err(AxiomViolation::IndexOutOfRange { word: vec![0, 20], index: 20 })
+        );
+        assert_eq!(HyperbolicManifold::invert_path(&[0, 1, 12]), vec![2, 11, 10]);
+    }
+
+    #[test]
+    fn test_matches_descriptor() {
+        let m = HyperbolicManifold::new();
+        let desc_m = m.descriptor();
+        let traces = m.generator_traces();
+        assert_eq!(traces[0], Fr::from(3));
+        assert!(HyperbolicManifold::matches_descriptor(desc_m, 5, -8, 12345, traces));
+
+        assert!(!HyperbolicManifold::matches_descriptor(desc_m, 4, -8, 12345, traces));
+        assert!(!HyperbolicManifold::matches_descriptor(desc_m, 5, 8, 12345, traces));
+        assert!(!HyperbolicManifold::matches_descriptor(desc_m, 5, -8, 12346, traces));
+        let mut altered = traces;
+        altered[9] += Fr::one();
+        assert!(!HyperbolicManifold::matches_descriptor(desc_m, 5, -8, 12345, altered));
+        let mut other_desc = desc_m;
+        other_desc[0] += Fr::one();
+        assert!(!HyperbolicManifold::matches_descriptor(other_desc, 5, -8, 12345, traces));
+    }
+
+    #[test]
+    fn test_out_of_range_generator_is_none() {
+        let m = HyperbolicManifold::new();
+        assert!(m.get_generator(19).is_some());
+        assert_eq!(m.get_generator(25), None);
+        assert_eq!(m.try_holonomy(&[0, 1, 25, 3]), Err(2));
+        assert_eq!(m.try_holonomy(&[0, 1]), Ok(m.holonomy(&[0, 1])));
+    }
+
+    #[test]
+    fn test_generator_labels() {
+        let labels = HyperbolicManifold::generator_labels();
+        assert_eq!(labels.len(), 20);
+        for (idx, label) in labels.iter().enumerate() {
+            assert_eq!(HyperbolicManifold::generator_label(idx), label);
+            assert_eq!(HyperbolicManifold::generator_index(label), Some(idx));
+        }
+        assert_eq!(HyperbolicManifold::generator_label(0), "a1");
+        assert_eq!(HyperbolicManifold::generator_label(9), "b5");
+        assert_eq!(HyperbolicManifold::generator_label(12), "a2_inv");
+        assert_eq!(HyperbolicManifold::generator_index("c1"), None);
+    }
+
+    #[test]
+    fn test_word_format() {
+        let manifold = HyperbolicManifold::new();
+        assert_eq!(manifold.word(&[0, 5, 12, 9]), "a1 b3 a2⁻¹ b5");
+        assert_eq!(manifold.word(&[]), "");
+        assert_eq!(HyperbolicManifold::parse_word("a1 b3 a2⁻¹ b5"), Some(vec![0, 5, 12, 9]));
+        assert_eq!(HyperbolicManifold::parse_word("a1  a2_inv"), Some(vec![0, 12]));
+        assert_eq!(HyperbolicManifold::parse_word("a1 c2"), None);
+        assert_eq!(HyperbolicManifold::parse_word("a1_inv⁻¹"), None);
+    }
+
+    #[test]
+    fn test_holonomy_reverse_convention() {
+        let m = HyperbolicManifold::new();
+        // Hol([g1, g2]) = G2·G1
+        let expected = HyperbolicManifold::mat_mul(m.get_generator(7).unwrap(), m.get_generator(3).unwrap());
+        assert_eq!(m.holonomy(&[3, 7]), [expected.0, expected.1, expected.2, expected.3]);
+    }
+
+    #[test]
+    fn test_reverse_convention_is_the_homomorphism() {
+        use rand::{Rng, SeedableRng};
+        use rand_chacha::ChaCha20Rng;
+
+        // The two candidate conventions, written out independently of the crate
+        let m = HyperbolicManifold::new();
+        let generators = m.field_generators();
+        let g = |idx: u8| generator_over(&generators, idx as usize);
+        // Natural: Hol(γ) = G(γ₁)·...·G(γₙ)
+        let natural = |path: &[u8]| path.iter().fold(Sl2Matrix::<Fr>::identity(), |acc, &idx| acc.mul(&g(idx)));
+        // Reverse: Hol(γ) = G(γₙ)·...·G(γ₁)
+        let reverse = |path: &[u8]| path.iter().fold(Sl2Matrix::<Fr>::identity(), |acc, &idx| g(idx).mul(&acc));
+        let as_array = |h: Sl2Matrix<Fr>| [h.a, h.b, h.c, h.d];
+
+        let mut rng = ChaCha20Rng::from_seed([193u8; 32]);
+        let words: Vec<Vec<u8>> = (0..8)
+            .map(|_| (0..rng.gen_range(2..=10)).map(|_| rng.gen_range(0..20)).collect())
+            .collect();
+        let mut natural_composes = true;
+        for u in &words {
+            // Every holonomy implementation in the crate follows the reverse convention
+            assert_eq!(m.holonomy(u), as_array(reverse(u)));
+            assert_eq!(HolonomyGroup::try_holonomy(&m, u), Ok(as_array(reverse(u))));
+            assert_eq!(holonomy_over(&generators, u), reverse(u));
+
+            // Hol(w⁻¹) = adj(Hol(w)), the inverse wherever det = 1
+            assert_eq!(reverse(&HyperbolicManifold::invert_path(u)), reverse(u).adjugate());
+            for v in &words {
+                let uv = [u.as_slice(), v.as_slice()].concat();
+                // Hol(uv) = Hol(v)·Hol(u)
+                assert_eq!(reverse(&uv), reverse(v).mul(&reverse(u)));
+                natural_composes &= natural(&uv) == natural(v).mul(&natural(u));
+            }
+        }
+        assert!(!natural_composes, "the natural convention must not pass as Hol(uv) = Hol(v)·Hol(u)");
+    }
+
+    #[test]
+    fn test_is_combined_reduced() {
+        // a1 b1 || a2 b2: clean junction
+        assert!(HyperbolicManifold::is_combined_reduced(&[0, 1], &[2, 3]));
+        // a1 b1 || b1⁻¹ a2: b1 cancels across the junction
+        assert!(!HyperbolicManifold::is_combined_reduced(&[0, 1], &[11, 2]));
+        // Internally unreduced halves fail even with a clean junction
+        assert!(!HyperbolicManifold::is_combined_reduced(&[0, 10], &[2, 3]));
+        assert!(!HyperbolicManifold::is_combined_reduced(&[0, 1], &[2, 12]));
+        assert!(HyperbolicManifold::is_combined_reduced(&[], &[2, 3]));
+        assert!(HyperbolicManifold::is_combined_reduced(&[0, 1], &[]));
+    }
+
+    #[test]
+    fn test_verify_signature_composition() {
+        let m = HyperbolicManifold::new();
+        let gamma = vec![0u8, 5, 12, 7, 19, 3];
+        let delta = vec![4u8, 9, 1, 16];
+        let h_pub = m.holonomy(&gamma);
+        let combined: Vec<u8> = gamma.iter().chain(delta.iter()).copied().collect();
+        let h_sig = m.holonomy(&combined);
+        assert!(m.verify_signature_composition(h_pub, &delta, h_sig));
+
+        let mut tampered_sig = h_sig;
+        tampered_sig[1] += Fr::one();
+        assert!(!m.verify_signature_composition(h_pub, &delta, tampered_sig));
+
+        let mut tampered_delta = delta.clone();
+        tampered_delta[2] = 2;
+        assert!(!m.verify_signature_composition(h_pub, &tampered_delta, h_sig));
+    }
+
+    #[test]
+    fn test_descriptor_non_degenerate_and_stable() {
+        let m1 = HyperbolicManifold::new();
+        let m2 = HyperbolicManifold::new();
+        assert!(HyperbolicManifold::check_descriptor_non_degenerate(m1.descriptor()).is_ok());
+        assert_eq!(m1.descriptor(), m2.descriptor());
+        assert_eq!(HyperbolicManifold::canonical_descriptor().ok(), Some(m1.descriptor()));
+
+        for degenerate in [[Fr::zero(); 4], [Fr::one(), Fr::zero(), Fr::zero(), Fr::one()]] {
+            assert!(matches!(
+                HyperbolicManifold::check_descriptor_non_degenerate(degenerate),
+                Err(ToposhieldError::DegenerateDescriptor { desc_m }) if desc_m == degenerate
+            ));
+        }
+    }
+
+    /// Reduce an exact integer matrix into Fr
+    fn int_to_fr(m: &Sl2Matrix<i128>) -> Sl2Matrix<Fr> {
+        let to_fr = |x: i128| {
+            let abs = Fr::from_u128(x.unsigned_abs());
+            if x < 0 { -abs } else { abs }
+        };
+        Sl2Matrix::new(to_fr(m.a), to_fr(m.b), to_fr(m.c), to_fr(m.d))
+    }
+
+    #[test]
+    fn test_holonomy_over_integers_matches_field() {
+        let m = HyperbolicManifold::new();
+        let ints = HyperbolicManifold::integer_generators();
+        let path = [0u8, 5, 12, 7, 19, 3, 14, 8];
+        let h_int = holonomy_over(&ints, &path);
+        let h = m.holonomy(&path);
+        assert_eq!(int_to_fr(&h_int), Sl2Matrix::new(h[0], h[1], h[2], h[3]));
+        assert_eq!(holonomy_over(&m.field_generators(), &path), Sl2Matrix::new(h[0], h[1], h[2], h[3]));
+    }
+
+    #[test]
+    fn test_relator_over_integers_matches_field() {
+        // ∏[A_i, B_i] evaluated exactly in ℤ; reducing it mod p must give the
+        // field relator checked by `test_commutator_relation`
+        let ints = HyperbolicManifold::integer_generators();
+        let m = HyperbolicManifold::new();
+        let mut relator_int = Sl2Matrix::<i128>::identity();
+        let mut relator_fr = Sl2Matrix::<Fr>::identity();
+        for i in 0..5 {
+            // [A, B] = A·B·A⁻¹·B⁻¹ as the word (B⁻¹, A⁻¹, B, A) in the reverse convention
+            let word = [(2 * i + 11) as u8, (2 * i + 10) as u8, (2 * i + 1) as u8, (2 * i) as u8];
+            relator_int = relator_int.mul(&holonomy_over(&ints, &word));
+            relator_fr = relator_fr.mul(&holonomy_over(&m.field_generators(), &word));
+        }
+        assert_eq!(int_to_fr(&relator_int), relator_fr);
+    }
 }
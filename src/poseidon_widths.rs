@@ -0,0 +1,61 @@
+// src/poseidon_widths.rs
+// Poseidon instantiation (state width T, rate) of every hashed value
+// Each call site names its pair, so a value can move to another width on its own
+//
+// | Value                  | Rust site                     | T  | RATE | Circom counterpart                              |
+// |------------------------|-------------------------------|----|------|-------------------------------------------------|
+// | desc_M                 | HyperbolicManifold::descriptor| 15 | 14   | `PoseidonEx(14, 4)`: circomlib parameters, see below |
+// | m_hash                 | Witness::hash_to_4fr          | 4  | 1    | none: public input, hashed off-circuit          |
+// | epoch-bound m_hash     | Witness::epoch_message_hash   | 4  | 1    | none: public input, hashed off-circuit          |
+// | derived seeds          | Witness::derive_seed          | 4  | 1    | none                                            |
+// | path PRF               | Witness::generate_path_with   | 4  | 1    | none: the circuit takes the indices as private inputs |
+// | audit commitment       | Witness::commitment           | 4  | 1    | none                                            |
+// | Poseidon spec id       | Witness::poseidon_spec_id     | 4  | 1    | none: fingerprints the Rust parameter set       |
+// | Merkle leaves/nodes    | stateful::hash_leaf/hash_node | 4  | 1    | none                                            |
+//
+// desc_M is the only value the circuit recomputes. Both sides permute the 15-element
+// state [0, DOMAIN_DESC, genus, χ, p_inv, tr(a1), ..., tr(b5)] once with circomlib's
+// parameters (`circom_poseidon::CircomPoseidon`) and take the first 4 elements;
+// `test_desc_m_matches_circuit_poseidon` checks this against the compiled circuit.
+// The other rows use the `poseidon` crate's own parameters, which circomlib does not
+// share, so none of them can move into the circuit without switching to it too.
+// Changing a width changes every value hashed with it, so desc_M also needs a
+// `DESCRIPTOR_VERSION` bump.
+use static_assertions::const_assert;
+
+pub const DESC_T: usize = 15;
+pub const DESC_RATE: usize = 14;
+
+pub const MHASH_T: usize = 4;
+pub const MHASH_RATE: usize = 1;
+
+pub const EPOCH_T: usize = 4;
+pub const EPOCH_RATE: usize = 1;
+
+pub const SEED_T: usize = 4;
+pub const SEED_RATE: usize = 1;
+
+pub const PATH_T: usize = 4;
+pub const PATH_RATE: usize = 1;
+
+pub const COMMIT_T: usize = 4;
+pub const COMMIT_RATE: usize = 1;
+
+pub const SPEC_ID_T: usize = 4;
+pub const SPEC_ID_RATE: usize = 1;
+
+pub const MERKLE_T: usize = 4;
+pub const MERKLE_RATE: usize = 1;
+
+// A sponge needs at least one capacity element: RATE < T
+const_assert!(DESC_RATE < DESC_T);
+const_assert!(MHASH_RATE < MHASH_T);
+const_assert!(EPOCH_RATE < EPOCH_T);
+const_assert!(SEED_RATE < SEED_T);
+const_assert!(PATH_RATE < PATH_T);
+const_assert!(COMMIT_RATE < COMMIT_T);
+const_assert!(SPEC_ID_RATE < SPEC_ID_T);
+const_assert!(MERKLE_RATE < MERKLE_T);
+
+// circomlib's `PoseidonEx(n, _)` permutes [initialState, n inputs] in one go
+const_assert!(DESC_T == DESC_RATE + 1);
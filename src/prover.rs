@@ -2,117 +2,1281 @@
 // TopoShield Prover: Halo2 + Circom integration for enhanced ZKP
 // Compatible with holonomy_path_enhanced.circom (genus=5, path_len=20)
 
-use crate::witness::Witness;
-use ff::Field;
+use crate::{
+    descriptor::DescriptorCompat,
+    error::{with_context, ToposhieldError},
+    instance::{InstanceLayout, PublicInputs},
+    manifold::{HyperbolicManifold, DESCRIPTOR_VERSION},
+    witness::Witness,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use ff::{Field, PrimeField};
 use halo2_circom::{
     circuit::{CircomCircuit, CircomConfig},
     plonk::CircomReduction,
 };
 use halo2_proofs::{
-    dev::MockProver,
-    halo2curves::bn256::{Bn256, Fr, G1Affine},
-    plonk::{create_proof, verify_proof, Error, ProvingKey, VerifyingKey},
+    SerdeFormat,
+    dev::{MockProver, VerifyFailure},
+    halo2curves::{
+        bn256::{Bn256, Fr, G1Affine, G2Affine},
+        group::{prime::PrimeCurveAffine, GroupEncoding},
+    },
+    plonk::{self, create_proof, verify_proof, Circuit, ConstraintSystem, ProvingKey, VerifyingKey},
     poly::{
-        commitment::ParamsProver,
+        commitment::{Params, ParamsProver},
         kzg::{
             commitment::{KZGCommitmentScheme, ParamsKZG},
             msm::DualMSM,
             multiopen::ProverSHPLONK,
             strategy::AccumulatorStrategy,
         },
+        VerificationStrategy,
     },
     transcript::{
         Blake2bRead, Blake2bWrite, Challenge255, TranscriptReadBuffer, TranscriptWriterBuffer,
     },
 };
-use std::{fs, io::Cursor};
+use rand::{CryptoRng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use static_assertions::assert_impl_all;
+use std::{
+    fs,
+    io::{Cursor, Read},
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+/// ZK proof bytes together with the Poseidon spec fingerprint they were produced under.
+/// desc_M is recomputed inside the circuit from constants, so the spec id is bound here
+/// rather than into desc_M itself.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Proof {
+    pub bytes: Vec<u8>,
+    pub poseidon_spec_id: Fr,
+}
+
+impl Proof {
+    /// SHA-256 of the proof bytes: a succinct commitment a relayer can publish in
+    /// place of the proof and check with `TopoShieldProver::verify_with_commitment`
+    pub fn transcript_hash(&self) -> [u8; 32] {
+        Sha256::digest(&self.bytes).into()
+    }
+}
+
+/// Format tag of `Proof::to_bundle_json`; bumped on any incompatible change
+pub const BUNDLE_FORMAT: &str = "toposhield-proof-bundle/1";
+
+/// A proof with everything needed to verify it, as read by `Proof::from_bundle_json`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProofBundle {
+    pub proof: Proof,
+    pub inputs: PublicInputs,
+    /// Descriptor version desc_m was computed under
+    pub descriptor_version: u32,
+    /// `TopoShieldProver::setup_fingerprint` of the prover that made the proof
+    pub setup_fingerprint: String,
+}
+
+/// JSON form of a `ProofBundle`: proof bytes in base64, field elements in hex
+/// ("0x" + little-endian repr, as in `Witness::to_circom_input`)
+#[derive(Serialize, Deserialize)]
+struct BundleJson {
+    format: String,
+    proof: String,
+    poseidon_spec_id: String,
+    h_pub: Vec<String>,
+    h_sig: Vec<String>,
+    desc_m: Vec<String>,
+    m_hash: Vec<String>,
+    descriptor_version: u32,
+    setup_fingerprint: String,
+}
+
+/// Hex encoding of a field element, "0x" + little-endian repr (as in `Witness::to_circom_input`)
+pub fn fr_to_hex(f: &Fr) -> String {
+    format!("0x{}", hex::encode(f.to_repr()))
+}
+
+/// Inverse of `fr_to_hex`; `None` unless exactly 32 bytes encoding a canonical element
+pub fn fr_from_hex(s: &str) -> Option<Fr> {
+    let bytes = hex::decode(s.trim_start_matches("0x")).ok()?;
+    let mut repr = <Fr as PrimeField>::Repr::default();
+    if bytes.len() != repr.as_ref().len() {
+        return None;
+    }
+    repr.as_mut().copy_from_slice(&bytes);
+    Option::from(Fr::from_repr(repr))
+}
+
+fn component_from_hex(name: &str, values: &[String]) -> Result<[Fr; 4], ToposhieldError> {
+    let malformed = |reason: String| ToposhieldError::MalformedBundle { reason };
+    if values.len() != 4 {
+        return Err(malformed(format!("{} has {} elements, expected 4", name, values.len())));
+    }
+    let mut out = [Fr::zero(); 4];
+    for (slot, value) in out.iter_mut().zip(values) {
+        *slot = fr_from_hex(value).ok_or_else(|| malformed(format!("{}: invalid field element {}", name, value)))?;
+    }
+    Ok(out)
+}
+
+impl Proof {
+    /// Self-contained JSON bundle: the proof, its public inputs, the descriptor
+    /// version and the setup fingerprint. `TopoShieldProver::verify_bundle` checks
+    /// it with no other information; the raw `bytes` stay the compact form.
+    pub fn to_bundle_json(&self, inputs: &PublicInputs, setup_fingerprint: &str) -> String {
+        let hex_all = |values: &[Fr; 4]| values.iter().map(fr_to_hex).collect();
+        let bundle = BundleJson {
+            format: BUNDLE_FORMAT.to_string(),
+            proof: BASE64.encode(&self.bytes),
+            poseidon_spec_id: fr_to_hex(&self.poseidon_spec_id),
+            h_pub: hex_all(&inputs.h_pub),
+            h_sig: hex_all(&inputs.h_sig),
+            desc_m: hex_all(&inputs.desc_m),
+            m_hash: hex_all(&inputs.m_hash),
+            descriptor_version: DESCRIPTOR_VERSION,
+            setup_fingerprint: setup_fingerprint.to_string(),
+        };
+        serde_json::to_string_pretty(&bundle).expect("bundle fields serialize")
+    }
+
+    /// Parse a bundle written by `to_bundle_json`
+    pub fn from_bundle_json(s: &str) -> Result<ProofBundle, ToposhieldError> {
+        let malformed = |reason: String| ToposhieldError::MalformedBundle { reason };
+        let bundle: BundleJson = serde_json::from_str(s).map_err(|e| malformed(e.to_string()))?;
+        if bundle.format != BUNDLE_FORMAT {
+            return Err(malformed(format!("format {:?}, expected {:?}", bundle.format, BUNDLE_FORMAT)));
+        }
+        let bytes = BASE64
+            .decode(&bundle.proof)
+            .map_err(|e| malformed(format!("proof: {}", e)))?;
+        let poseidon_spec_id = fr_from_hex(&bundle.poseidon_spec_id)
+            .ok_or_else(|| malformed("invalid poseidon_spec_id".to_string()))?;
+        Ok(ProofBundle {
+            proof: Proof { bytes, poseidon_spec_id },
+            inputs: PublicInputs {
+                h_pub: component_from_hex("h_pub", &bundle.h_pub)?,
+                h_sig: component_from_hex("h_sig", &bundle.h_sig)?,
+                desc_m: component_from_hex("desc_m", &bundle.desc_m)?,
+                m_hash: component_from_hex("m_hash", &bundle.m_hash)?,
+            },
+            descriptor_version: bundle.descriptor_version,
+            setup_fingerprint: bundle.setup_fingerprint,
+        })
+    }
+}
+
+/// Why a proof was accepted or rejected by `TopoShieldProver::verify_detailed`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyOutcome {
+    Valid,
+    /// The proof parsed but the KZG pairing check failed for these public inputs
+    InvalidPairing,
+    /// The public inputs don't fit the circuit, or the proof verifies with the
+    /// same components in a different order (a public-input ordering bug)
+    InstanceMismatch,
+    /// The proof bytes could not be read as a transcript for this verifying key
+    MalformedProof,
+}
+
+/// A signer's public key as a verifier holds it
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct PublicKey {
+    pub h_pub: [Fr; 4],
+    pub desc_m: [Fr; 4],
+}
+
+impl PublicKey {
+    /// Key on the canonical genus-5 manifold
+    pub fn new(h_pub: [Fr; 4]) -> Self {
+        Self {
+            h_pub,
+            desc_m: HyperbolicManifold::new().descriptor(),
+        }
+    }
+}
+
+impl From<&Witness> for PublicKey {
+    fn from(witness: &Witness) -> Self {
+        Self {
+            h_pub: witness.h_pub,
+            desc_m: witness.desc_m,
+        }
+    }
+}
 
+/// A proof together with the signature holonomy it proves
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Signature {
+    pub proof: Proof,
+    pub h_sig: [Fr; 4],
+}
+
+/// Options controlling proof generation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProverOptions {
+    /// Run the MockProver before the real proof. Roughly doubles proving cost;
+    /// a failure is returned as an error naming the unsatisfied constraints.
+    pub run_mock_prover: bool,
+}
+
+impl Default for ProverOptions {
+    /// Mock pass on in debug builds, off in release builds
+    fn default() -> Self {
+        Self {
+            run_mock_prover: cfg!(debug_assertions),
+        }
+    }
+}
+
+/// One independent proof and its public inputs, for `TopoShieldProver::verify_many`
+#[derive(Debug, Clone, Copy)]
+pub struct VerifyRequest<'a> {
+    pub proof: &'a Proof,
+    pub h_pub: [Fr; 4],
+    pub h_sig: [Fr; 4],
+    pub desc_m: [Fr; 4],
+    pub m_hash: [Fr; 4],
+}
+
+/// Proofs per rayon task for `verify_many` when no chunk size is set: about four
+/// tasks per thread, so uneven verify times still balance without paying per-proof
+/// scheduling overhead. Sweep `benches/verify_bench.rs` (verify_many/*) to tune.
+pub fn default_batch_chunk_size(batch_len: usize) -> usize {
+    let tasks = 4 * rayon::current_num_threads();
+    batch_len.div_ceil(tasks).max(1)
+}
+
+/// Wall-clock breakdown of a single `prove_timed` call
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProveMetrics {
+    /// Circom witness calculation (WASM) and instance assembly
+    pub witness_map: Duration,
+    /// MockProver run and constraint check (zero when skipped by `ProverOptions`)
+    pub mock_prover: Duration,
+    /// Real KZG proof generation (`create_proof` with the cached proving key)
+    pub create_proof: Duration,
+    /// End-to-end duration, including glue between phases
+    pub total: Duration,
+}
+
+/// Size metadata of the loaded R1CS, e.g. for CI growth checks or SRS sizing
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct R1csSummary {
+    pub num_constraints: usize,
+    /// Public input wires, excluding the constant "1" wire
+    pub num_public_inputs: usize,
+    /// Wires that are neither public nor the constant wire
+    pub num_private_wires: usize,
+    pub num_variables: usize,
+    pub aux_offset: usize,
+}
+
+/// Read KZG params from disk.
+/// With the `mmap` feature the SRS file is memory-mapped instead of copied into a
+/// heap buffer first, so peak memory during load is the parsed params alone.
+pub fn load_params(path: &Path) -> Result<ParamsKZG<Bn256>, Box<dyn std::error::Error>> {
+    #[cfg(feature = "mmap")]
+    {
+        let file = with_context(fs::File::open(path), "open KZG params", path)?;
+        // SAFETY: the SRS file is only read, and must not be modified while mapped
+        let mmap = with_context(unsafe { memmap2::Mmap::map(&file) }, "memory-map KZG params", path)?;
+        Ok(with_context(ParamsKZG::read::<_>(&mut &mmap[..]), "parse KZG params", path)?)
+    }
+    #[cfg(not(feature = "mmap"))]
+    {
+        load_params_buffered(path)
+    }
+}
+
+/// Read KZG params through an in-memory copy of the file (the default loader)
+pub fn load_params_buffered(path: &Path) -> Result<ParamsKZG<Bn256>, Box<dyn std::error::Error>> {
+    let bytes = with_context(fs::read(path), "read KZG params", path)?;
+    Ok(with_context(ParamsKZG::read::<_>(&mut Cursor::new(bytes)), "parse KZG params", path)?)
+}
+
+/// Sidecar file holding the SHA-256 of an SRS file: `<path>.sha256`
+pub fn params_hash_path(path: &Path) -> PathBuf {
+    let mut hash_path = path.as_os_str().to_owned();
+    hash_path.push(".sha256");
+    PathBuf::from(hash_path)
+}
+
+/// (Re)write the `.sha256` sidecar of the SRS at `path` from its current contents,
+/// in `sha256sum` format. Returns the hex digest.
+///
+/// This trusts whatever is on disk now: only rehash an SRS you just generated.
+pub fn write_params_hash(path: &Path) -> Result<String, ToposhieldError> {
+    let digest = hex::encode(Sha256::digest(with_context(fs::read(path), "read KZG params", path)?));
+    let name = path.file_name().map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().into_owned());
+    let hash_path = params_hash_path(path);
+    with_context(
+        fs::write(&hash_path, format!("{}  {}\n", digest, name)),
+        "write SRS hash",
+        &hash_path,
+    )?;
+    Ok(digest)
+}
+
+/// Read KZG params and check them against the `.sha256` sidecar, if one exists.
+/// A mismatch means the SRS was replaced or tampered with since it was hashed.
+pub fn load_and_verify_params(path: &Path) -> Result<ParamsKZG<Bn256>, Box<dyn std::error::Error>> {
+    load_and_verify_params_with(path, None)
+}
+
+/// `load_and_verify_params` with the hash read from `hash_path` instead of the
+/// sidecar. An explicit hash file must exist; the sidecar is only checked if present.
+pub fn load_and_verify_params_with(
+    path: &Path,
+    hash_path: Option<&Path>,
+) -> Result<ParamsKZG<Bn256>, Box<dyn std::error::Error>> {
+    let bytes = with_context(fs::read(path), "read KZG params", path)?;
+    let (hash_path, required) = match hash_path {
+        Some(hash_path) => (hash_path.to_path_buf(), true),
+        None => (params_hash_path(path), false),
+    };
+    if required || hash_path.exists() {
+        let recorded = with_context(fs::read_to_string(&hash_path), "read SRS hash", &hash_path)?;
+        let expected = recorded.split_whitespace().next().unwrap_or_default().to_lowercase();
+        let found = hex::encode(Sha256::digest(&bytes));
+        if expected != found {
+            return Err(ToposhieldError::SrsHashMismatch {
+                path: path.to_path_buf(),
+                expected,
+                found,
+            }
+            .into());
+        }
+    }
+    Ok(with_context(ParamsKZG::read::<_>(&mut Cursor::new(bytes)), "parse KZG params", path)?)
+}
+
+const R1CS_PATH: &str = "build/holonomy_path_enhanced.r1cs";
+const WASM_PATH: &str = "build/holonomy_path_enhanced.wasm";
+
+const STATE_PARAMS_FILE: &str = "kzg.srs";
+const STATE_PK_FILE: &str = "pk.bin";
+const STATE_MANIFEST_FILE: &str = "state.json";
+
+/// Describes a `save_state` snapshot and what it was built from
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct StateManifest {
+    /// `artifact_fingerprint` at save time
+    fingerprint: String,
+    aux_offset: usize,
+    /// Hex SHA-256 of the params and proving key files in the state directory.
+    /// Empty in manifests written before they were recorded, which count as stale.
+    #[serde(default)]
+    params_sha256: String,
+    #[serde(default)]
+    pk_sha256: String,
+}
+
+/// Contents of a state file, checked against the hash its manifest records
+fn read_state_file(path: &Path, expected_sha256: &str) -> Result<Vec<u8>, ToposhieldError> {
+    let bytes = fs::read(path).map_err(|e| ToposhieldError::CorruptState {
+        path: path.to_path_buf(),
+        reason: e.to_string(),
+    })?;
+    let found = hex::encode(Sha256::digest(&bytes));
+    if found != expected_sha256 {
+        return Err(ToposhieldError::CorruptState {
+            path: path.to_path_buf(),
+            reason: format!("SHA-256 is {}, the manifest records {}", found, expected_sha256),
+        });
+    }
+    Ok(bytes)
+}
+
+/// SHA-256 over the R1CS, the WASM and (if present) params/kzg.srs, each
+/// length-prefixed. Any change to the circuit or the SRS invalidates saved state.
+fn artifact_fingerprint() -> Result<String, ToposhieldError> {
+    let mut hasher = Sha256::new();
+    for path in [R1CS_PATH, WASM_PATH, "params/kzg.srs"] {
+        let bytes = match fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && path == "params/kzg.srs" => Vec::new(),
+            Err(source) => {
+                return Err(ToposhieldError::Io {
+                    operation: "read circuit artifact",
+                    path: path.into(),
+                    source,
+                })
+            }
+        };
+        hasher.update((bytes.len() as u64).to_le_bytes());
+        hasher.update(&bytes);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Rows halo2 reserves on top of the assigned ones (blinding factors and the rows
+/// around them), from the circuit's own `ConstraintSystem::minimum_rows`. The column
+/// layout of `CircomCircuit` does not depend on the R1CS, so this is a constant.
+fn reserved_rows() -> usize {
+    let mut cs = ConstraintSystem::<Fr>::default();
+    <CircomCircuit<Bn256> as Circuit<Fr>>::configure(&mut cs);
+    cs.minimum_rows()
+}
+
+/// Smallest SRS degree k whose 2^k rows fit the circuit: one row per R1CS
+/// constraint, one per public input, plus the rows halo2 reserves
+pub fn required_srs_k(num_constraints: usize, num_public_inputs: usize) -> u32 {
+    let rows = num_constraints + num_public_inputs + reserved_rows();
+    rows.next_power_of_two().trailing_zeros()
+}
+
+/// Smallest SRS degree k that fits a loaded circuit (see `required_srs_k`)
+pub fn minimum_k_for_circuit(r1cs: &halo2_circom::circuit::R1CS<Bn256>) -> u32 {
+    required_srs_k(r1cs.constraints.len(), r1cs.num_inputs - 1)
+}
+
+/// Params usable for `r1cs`: k ≥ `minimum_k_for_circuit` (`SrsTooSmall` otherwise,
+/// checked first since keygen fails deep inside halo2 on a small SRS) and
+/// `verify_srs_compatibility`
+fn check_params_fit(
+    r1cs: &halo2_circom::circuit::R1CS<Bn256>,
+    params: &ParamsKZG<Bn256>,
+) -> Result<(), ToposhieldError> {
+    let required_k = minimum_k_for_circuit(r1cs);
+    if params.k() < required_k {
+        return Err(ToposhieldError::SrsTooSmall {
+            required_k,
+            available_k: params.k(),
+        });
+    }
+    verify_srs_compatibility(params)
+}
+
+/// Degree of the SRS `new` generates when params/kzg.srs is missing (2^17 rows).
+/// Any SRS with k ≥ `minimum_k_for_circuit` works.
+pub const SRS_K: u32 = 17;
+
+/// Approximate resident bytes of a degree-k `ParamsKZG<Bn256>`: 2^k G1 powers, as
+/// many Lagrange-basis points, and the two G2 elements. Setup and serialization
+/// need transient buffers on top of this.
+pub fn estimate_srs_memory(k: u32) -> usize {
+    let n = 1usize << k;
+    2 * n * std::mem::size_of::<G1Affine>() + 2 * std::mem::size_of::<G2Affine>()
+}
+
+/// `MemAvailable` from /proc/meminfo in bytes; `None` where that is unavailable
+pub fn available_memory() -> Option<usize> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let line = meminfo.lines().find(|line| line.starts_with("MemAvailable:"))?;
+    let kib: usize = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+/// Sanity-check loaded KZG params before keygen: 2^k G1 powers for their degree k,
+/// the G2 elements, and that the powers start at the canonical BN254 G1 generator.
+/// A right-sized SRS over another base point (swapped curve, corruption) fails here.
+/// Whether k is large enough for the circuit is `check_params_fit`'s job.
+pub fn verify_srs_compatibility(params: &ParamsKZG<Bn256>) -> Result<(), ToposhieldError> {
+    let g = params.get_g();
+    if g.len() != 1 << params.k() {
+        return Err(ToposhieldError::SrsIncompatible {
+            reason: format!("{} G1 powers, expected {} for k = {}", g.len(), 1u64 << params.k(), params.k()),
+        });
+    }
+    if params.g2() != G2Affine::generator() || bool::from(params.s_g2().is_identity()) {
+        return Err(ToposhieldError::SrsIncompatible {
+            reason: "missing or invalid G2 elements".to_string(),
+        });
+    }
+    if g[0] != G1Affine::generator() {
+        return Err(ToposhieldError::SrsWrongGenerator);
+    }
+    Ok(())
+}
+
+/// The part of a `ParamsKZG<Bn256>` that KZG/SHPLONK verification reads: the degree,
+/// the first G1 power and the two G2 elements. A verifier that never proves can ship
+/// this instead of the multi-megabyte SRS; see `verify_minimal`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MinimalVerifierParams {
+    pub k: u32,
+    pub g: G1Affine,
+    pub g2: G2Affine,
+    pub s_g2: G2Affine,
+}
+
+impl MinimalVerifierParams {
+    /// Serialized size: k (u32 LE), then the compressed G1 and two G2 points
+    pub const BYTES: usize = 4 + 32 + 2 * 64;
+
+    pub fn from_params(params: &ParamsKZG<Bn256>) -> Self {
+        Self { k: params.k(), g: params.get_g()[0], g2: params.g2(), s_g2: params.s_g2() }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::BYTES);
+        bytes.extend_from_slice(&self.k.to_le_bytes());
+        bytes.extend_from_slice(self.g.to_bytes().as_ref());
+        bytes.extend_from_slice(self.g2.to_bytes().as_ref());
+        bytes.extend_from_slice(self.s_g2.to_bytes().as_ref());
+        bytes
+    }
+
+    /// Inverse of `to_bytes`; points off the curve or a wrong length are rejected
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ToposhieldError> {
+        let malformed = |what: &str| ToposhieldError::SrsIncompatible {
+            reason: format!("minimal verifier params: {what}"),
+        };
+        if bytes.len() != Self::BYTES {
+            return Err(malformed(&format!("{} bytes, expected {}", bytes.len(), Self::BYTES)));
+        }
+        let (k, rest) = bytes.split_at(4);
+        let (g, rest) = rest.split_at(32);
+        let (g2, s_g2) = rest.split_at(64);
+
+        let mut g_repr = <G1Affine as GroupEncoding>::Repr::default();
+        g_repr.as_mut().copy_from_slice(g);
+        let mut g2_repr = <G2Affine as GroupEncoding>::Repr::default();
+        g2_repr.as_mut().copy_from_slice(g2);
+        let mut s_g2_repr = <G2Affine as GroupEncoding>::Repr::default();
+        s_g2_repr.as_mut().copy_from_slice(s_g2);
+
+        Ok(Self {
+            k: u32::from_le_bytes(k.try_into().expect("split at 4")),
+            g: Option::from(G1Affine::from_bytes(&g_repr)).ok_or_else(|| malformed("invalid G1 point"))?,
+            g2: Option::from(G2Affine::from_bytes(&g2_repr)).ok_or_else(|| malformed("invalid g2"))?,
+            s_g2: Option::from(G2Affine::from_bytes(&s_g2_repr)).ok_or_else(|| malformed("invalid s_g2"))?,
+        })
+    }
+
+    /// A `ParamsKZG` holding only these elements. Good for verification only: it has a
+    /// single G1 power and no Lagrange basis, so committing with it panics.
+    fn to_params(&self) -> ParamsKZG<Bn256> {
+        // `from_parts` is a method and the fields are private; a degree-1 setup, made
+        // once, serves as the receiver and none of its fields survive
+        static TEMPLATE: OnceLock<ParamsKZG<Bn256>> = OnceLock::new();
+        TEMPLATE
+            .get_or_init(|| ParamsKZG::<Bn256>::setup(1, ChaCha20Rng::seed_from_u64(0)))
+            .from_parts(self.k, vec![self.g], Some(Vec::new()), self.g2, self.s_g2)
+    }
+}
+
+/// Verify a proof with only a verifying key and `MinimalVerifierParams`, no full SRS.
+/// `instance` is the raw public-input column (see `InstanceLayout::instances`); no
+/// descriptor or holonomy policy is applied. Agrees with `TopoShieldProver::verify_instance`
+/// whenever `params` was extracted from the prover's SRS.
+pub fn verify_minimal(
+    vk: &VerifyingKey<G1Affine>,
+    params: &MinimalVerifierParams,
+    proof: &Proof,
+    instance: &[Fr],
+) -> Result<bool, ToposhieldError> {
+    let params = params.to_params();
+    let instances = vec![instance.to_vec()];
+    let outcome = verify_with_params(&params, vk, proof.bytes.as_slice(), &[instances.as_slice()])?;
+    Ok(outcome == VerifyOutcome::Valid)
+}
+
+/// KZG/SHPLONK verification of a transcript, with halo2 errors classified into outcomes
+fn verify_with_params<R: Read>(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    reader: R,
+    instances: &[&[Vec<Fr>]],
+) -> Result<VerifyOutcome, ToposhieldError> {
+    let strategy = AccumulatorStrategy::new(params);
+    let mut transcript = Blake2bRead::<_, G1Affine, Challenge255<_>>::init(reader);
+    let result = verify_proof::<
+        KZGCommitmentScheme<Bn256>,
+        halo2_proofs::poly::kzg::multiopen::VerifierSHPLONK<_>,
+        Challenge255<_>,
+        AccumulatorStrategy<_>,
+        _,
+        Blake2bRead<_, _, _>,
+    >(params, vk, strategy, instances, &mut transcript);
+
+    match result {
+        // The accumulated pairing is only checked on finalize
+        Ok(strategy) if strategy.finalize() => Ok(VerifyOutcome::Valid),
+        Ok(_) | Err(plonk::Error::Opening) | Err(plonk::Error::ConstraintSystemFailure) => {
+            Ok(VerifyOutcome::InvalidPairing)
+        }
+        Err(plonk::Error::InvalidInstances) => Ok(VerifyOutcome::InstanceMismatch),
+        Err(plonk::Error::Transcript(_)) => Ok(VerifyOutcome::MalformedProof),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// desc_M must be a registered descriptor version and, if pinned, an accepted manifold
+fn check_descriptor_policy(
+    compat: &DescriptorCompat,
+    accepted_manifolds: Option<&[[Fr; 4]]>,
+    desc_m: [Fr; 4],
+) -> Result<(), ToposhieldError> {
+    if compat.accepts(&desc_m).is_none() {
+        return Err(ToposhieldError::UnsupportedDescriptor { desc_m });
+    }
+    if let Some(accepted) = accepted_manifolds {
+        if !accepted.contains(&desc_m) {
+            return Err(ToposhieldError::UnacceptedManifold { desc_m });
+        }
+    }
+    Ok(())
+}
+
+/// Verification-only counterpart of `TopoShieldProver`, for memory-limited deployments.
+///
+/// It keeps the verifying key and a `ParamsKZG` rebuilt from `MinimalVerifierParams`
+/// (one G1 power, two G2 points) instead of the full SRS, whose 2·2^k G1 points take
+/// about 16 MiB at k = 17 and double with each k (see `estimate_srs_memory`).
+/// What it retains is independent of k: the VK holds one commitment per fixed and
+/// permutation column and the constraint system, both fixed by the circuit, and
+/// verification itself works on the proof's commitments and evaluations, never on a
+/// 2^k-sized vector. `retained_size` computes that retained size; it is not a
+/// measurement of heap usage.
+pub struct TopoShieldVerifier {
+    params: ParamsKZG<Bn256>,
+    vk: VerifyingKey<G1Affine>,
+    poseidon_spec_id: Fr,
+    instance_layout: InstanceLayout,
+    descriptor_compat: DescriptorCompat,
+    accepted_manifolds: Option<Vec<[Fr; 4]>>,
+}
+
+assert_impl_all!(TopoShieldVerifier: Send, Sync);
+
+impl TopoShieldVerifier {
+    /// Verifier from a verifying key and minimal params, e.g. both shipped as files.
+    /// The Poseidon spec and descriptor policy are this build's defaults.
+    pub fn new(vk: VerifyingKey<G1Affine>, params: &MinimalVerifierParams) -> Self {
+        Self {
+            params: params.to_params(),
+            vk,
+            poseidon_spec_id: Witness::poseidon_spec_id(),
+            instance_layout: InstanceLayout::default(),
+            descriptor_compat: DescriptorCompat::default(),
+            accepted_manifolds: None,
+        }
+    }
+
+    /// Same checks and result as `TopoShieldProver::verify`
+    pub fn verify(&self, proof: &Proof, inputs: &PublicInputs) -> Result<bool, ToposhieldError> {
+        if proof.poseidon_spec_id != self.poseidon_spec_id {
+            return Err(ToposhieldError::PoseidonSpecMismatch {
+                expected: self.poseidon_spec_id,
+                found: proof.poseidon_spec_id,
+            });
+        }
+        TopoShieldProver::check_public_holonomies(inputs.h_pub, inputs.h_sig)?;
+        check_descriptor_policy(&self.descriptor_compat, self.accepted_manifolds.as_deref(), inputs.desc_m)?;
+
+        let instances = self.instance_layout.instances(inputs);
+        let outcome =
+            verify_with_params(&self.params, &self.vk, proof.bytes.as_slice(), &[instances.as_slice()])?;
+        Ok(outcome == VerifyOutcome::Valid)
+    }
+
+    /// Retained size in bytes, computed rather than measured: the serialized VK plus
+    /// the retained curve points. Depends on the circuit's shape, not on the SRS degree.
+    pub fn retained_size(&self) -> usize {
+        let points = self.params.get_g().len() * std::mem::size_of::<G1Affine>()
+            + 2 * std::mem::size_of::<G2Affine>();
+        self.vk.to_bytes(SerdeFormat::RawBytes).len() + points
+    }
+}
+
+/// Prover and verifier for the TopoShield circuit.
+///
+/// `TopoShieldProver` is `Send + Sync` and every proving and verifying method takes
+/// `&self`, so one instance can be shared across threads in an `Arc` and called
+/// concurrently; each call keeps its transcript and RNG on its own stack. Builders
+/// (`with_*`) take `self` by value and are meant for setup, before sharing.
 pub struct TopoShieldProver {
     params: ParamsKZG<Bn256>,
     pk: ProvingKey<G1Affine>,
     vk: VerifyingKey<G1Affine>,
     r1cs: halo2_circom::circuit::R1CS<Bn256>,
     aux_offset: usize,
+    /// Witness-index → R1CS-wire mapping; required when circom optimization
+    /// (e.g. --O2) removed wires so the WASM witness and R1CS wire order differ
+    wire_mapping: Option<Vec<usize>>,
+    poseidon_spec_id: Fr,
+    /// desc_M of the manifold this prover proves for, computed once at construction
+    desc_m: [Fr; 4],
+    instance_layout: InstanceLayout,
+    descriptor_compat: DescriptorCompat,
+    /// Optional allow-list of manifolds, checked on top of `descriptor_compat`
+    accepted_manifolds: Option<Vec<[Fr; 4]>>,
+    /// Proofs per rayon task in `verify_many`; `None` uses `default_batch_chunk_size`
+    batch_chunk_size: Option<usize>,
 }
 
+// Sharing a prover across threads is part of the API; a non-Sync field must fail here
+assert_impl_all!(TopoShieldProver: Send, Sync);
+
 impl TopoShieldProver {
     /// Инициализирует прувера: загружает R1CS, WASM и KZG-параметры
     pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
+        Self::new_with_rng(rand::rngs::OsRng)
+    }
+
+    /// Same as `new`, but draws the KZG setup randomness (only used when
+    /// params/kzg.srs is missing) from the supplied RNG instead of `OsRng`.
+    ///
+    /// SECURITY: the RNG output determines the SRS toxic waste τ. It must be a
+    /// cryptographically secure generator seeded from secret, unpredictable entropy;
+    /// anyone who can reproduce its output can forge proofs against this SRS.
+    pub fn new_with_rng<R: RngCore + CryptoRng>(
+        mut rng: R,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
         // Загрузка Circom-артефактов
-        let config = CircomConfig::<Bn256>::new(
-            "build/holonomy_path_enhanced.r1cs",
-            "build/holonomy_path_enhanced.wasm",
-        )?;
+        let config = Self::load_circom_config()?;
+        let params = Self::load_or_setup_params(&mut rng)?;
+        Self::keygen(config, params)
+    }
+
+    /// Build a prover from in-memory R1CS and WASM artifacts (e.g. `include_bytes!`),
+    /// for deployments without build/ on disk. KZG params are loaded as in `new`.
+    /// `CircomConfig` only reads paths, so the bytes go through a private temp
+    /// directory that is removed once they are parsed.
+    pub fn from_embedded(r1cs_bytes: &[u8], wasm_bytes: &[u8]) -> Result<Self, Box<dyn std::error::Error>> {
+        let config = Self::circom_config_from_bytes(r1cs_bytes, wasm_bytes)?;
+        let params = Self::load_or_setup_params(&mut rand::rngs::OsRng)?;
+        Self::keygen(config, params)
+    }
 
+    /// Load params/kzg.srs, or run a fresh `SRS_K` setup from `rng` if it is missing
+    fn load_or_setup_params<R: RngCore + CryptoRng>(
+        rng: &mut R,
+    ) -> Result<ParamsKZG<Bn256>, Box<dyn std::error::Error>> {
         // Загрузка или генерация KZG SRS
         let params_path = "params/kzg.srs";
-        let params = if std::path::Path::new(params_path).exists() {
-            let bytes = fs::read(params_path)?;
-            ParamsKZG::read::<_>(&mut Cursor::new(bytes))?
+        let params = if Path::new(params_path).exists() {
+            load_and_verify_params(Path::new(params_path))?
         } else {
-            eprintln!("⚠️  KZG setup not found at params/kzg.srs — generating (k=17)...");
-            let params = ParamsKZG::<Bn256>::setup(17, rand::rngs::OsRng);
-            fs::create_dir_all("params")?;
-            let mut file = fs::File::create(params_path)?;
-            params.write(&mut file)?;
+            eprintln!("⚠️  KZG setup not found at params/kzg.srs — generating (k={})...", SRS_K);
+            let params = ParamsKZG::<Bn256>::setup(SRS_K, rng);
+            with_context(fs::create_dir_all("params"), "create directory", "params")?;
+            let mut file = with_context(fs::File::create(params_path), "create KZG params", params_path)?;
+            with_context(params.write(&mut file), "write KZG params", params_path)?;
+            write_params_hash(Path::new(params_path))?;
             params
         };
+        Ok(params)
+    }
+
+    /// Build a prover from already-loaded KZG params, running keygen against the
+    /// Circom artifacts in build/. The SRS must be large enough for the circuit
+    /// (`SrsTooSmall` otherwise) and match `verify_srs_compatibility`.
+    pub fn from_params(params: ParamsKZG<Bn256>) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::keygen(Self::load_circom_config()?, params)
+    }
+
+    /// Build a prover from an SRS stored outside params/, checked against `hash`
+    /// (default: the `<srs>.sha256` sidecar, if present). Unlike `new`, a missing SRS
+    /// is an error rather than a fresh setup.
+    pub fn with_srs_path(srs: PathBuf, hash: Option<PathBuf>) -> Result<Self, Box<dyn std::error::Error>> {
+        let params = load_and_verify_params_with(&srs, hash.as_deref())?;
+        Self::from_params(params)
+    }
+
+    fn keygen(
+        config: CircomConfig<Bn256>,
+        params: ParamsKZG<Bn256>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        check_params_fit(&config.r1cs, &params)?;
 
         // Пустая схема для генерации ключей
         let empty_circuit = CircomCircuit {
             r1cs: config.r1cs.clone(),
             witness: Some(vec![]),
-            wire_mapping: None,
+            wire_mapping: config.r1cs.wire_mapping.clone(),
             aux_offset: config.aux_offset,
         };
 
         let vk = halo2_proofs::plonk::keygen_vk(&params, &empty_circuit)?;
-        let pk = halo2_proofs::plonk::keygen_pk(&params, vk.clone(), &empty_circuit)?;
+        let pk = halo2_proofs::plonk::keygen_pk(&params, vk, &empty_circuit)?;
+        Self::from_parts(config, params, pk)
+    }
+
+    fn load_circom_config() -> Result<CircomConfig<Bn256>, Box<dyn std::error::Error>> {
+        Ok(CircomConfig::<Bn256>::new(R1CS_PATH, WASM_PATH)
+            .map_err(|e| format!("failed to load Circom artifacts from build/: {}", e))?)
+    }
+
+    fn circom_config_from_bytes(
+        r1cs_bytes: &[u8],
+        wasm_bytes: &[u8],
+    ) -> Result<CircomConfig<Bn256>, Box<dyn std::error::Error>> {
+        // Created with a unique name and owner-only permissions; removed on drop
+        let dir = with_context(
+            tempfile::Builder::new().prefix("toposhield-embedded-").tempdir(),
+            "create directory",
+            std::env::temp_dir(),
+        )?;
+        let (r1cs_path, wasm_path) = (dir.path().join("circuit.r1cs"), dir.path().join("circuit.wasm"));
+        with_context(fs::write(&r1cs_path, r1cs_bytes), "write embedded R1CS", &r1cs_path)?;
+        with_context(fs::write(&wasm_path, wasm_bytes), "write embedded WASM", &wasm_path)?;
+        Ok(CircomConfig::<Bn256>::new(&r1cs_path.to_string_lossy(), &wasm_path.to_string_lossy())
+            .map_err(|e| format!("failed to load embedded Circom artifacts: {}", e))?)
+    }
+
+    /// Assemble a ready prover from loaded artifacts, params and a proving key
+    fn from_parts(
+        config: CircomConfig<Bn256>,
+        params: ParamsKZG<Bn256>,
+        pk: ProvingKey<G1Affine>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        // Публичные входы схемы (без константного провода "1")
+        let instance_layout = InstanceLayout::default();
+        instance_layout.validate(config.r1cs.num_inputs - 1)?;
+
+        // Карта проводов из секции R1CS (если circom записал её при оптимизации)
+        let wire_mapping = config.r1cs.wire_mapping.clone();
 
         Ok(Self {
             params,
+            vk: pk.get_vk().clone(),
             pk,
-            vk,
             r1cs: config.r1cs,
             aux_offset: config.aux_offset,
+            wire_mapping,
+            poseidon_spec_id: Witness::poseidon_spec_id(),
+            desc_m: HyperbolicManifold::canonical_descriptor()?,
+            instance_layout,
+            descriptor_compat: DescriptorCompat::default(),
+            accepted_manifolds: None,
+            batch_chunk_size: None,
         })
     }
 
+    /// Snapshot the ready-to-prove state into `dir`: params, proving key (which
+    /// contains the verifying key), their hashes and a fingerprint of the Circom
+    /// artifacts and SRS file it was built from. `load_state` then skips keygen entirely.
+    ///
+    /// Builder settings (wire mapping file, layout, descriptor registry, pins) are not
+    /// saved; reapply them after loading.
+    pub fn save_state(&self, dir: impl AsRef<Path>) -> Result<(), Box<dyn std::error::Error>> {
+        let dir = dir.as_ref();
+        with_context(fs::create_dir_all(dir), "create directory", dir)?;
+
+        let params_path = dir.join(STATE_PARAMS_FILE);
+        let mut params_bytes = Vec::new();
+        with_context(self.params.write(&mut params_bytes), "serialize KZG params", &params_path)?;
+        with_context(fs::write(&params_path, &params_bytes), "write KZG params", &params_path)?;
+
+        let pk_path = dir.join(STATE_PK_FILE);
+        let mut pk_bytes = Vec::new();
+        with_context(self.pk.write(&mut pk_bytes, SerdeFormat::RawBytes), "serialize proving key", &pk_path)?;
+        with_context(fs::write(&pk_path, &pk_bytes), "write proving key", &pk_path)?;
+
+        // Written last: a state without a manifest is never loaded
+        let manifest = StateManifest {
+            fingerprint: artifact_fingerprint()?,
+            aux_offset: self.aux_offset,
+            params_sha256: hex::encode(Sha256::digest(&params_bytes)),
+            pk_sha256: hex::encode(Sha256::digest(&pk_bytes)),
+        };
+        let manifest_path = dir.join(STATE_MANIFEST_FILE);
+        with_context(
+            fs::write(&manifest_path, serde_json::to_vec_pretty(&manifest)?),
+            "write prover state manifest",
+            &manifest_path,
+        )?;
+        Ok(())
+    }
+
+    /// Restore a prover saved by `save_state`.
+    ///
+    /// Missing or stale state (no manifest, or a fingerprint that no longer matches
+    /// the Circom artifacts and SRS on disk) is rebuilt with `new` and saved to `dir`
+    /// in its place. Current state that is damaged (an unparsable manifest, or params
+    /// or proving key missing, altered or unparsable) is a `CorruptState` error and
+    /// is left untouched for inspection.
+    pub fn load_state(dir: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        let dir = dir.as_ref();
+        let manifest_path = dir.join(STATE_MANIFEST_FILE);
+        let fingerprint = artifact_fingerprint()?;
+        let manifest: Option<StateManifest> = match fs::read(&manifest_path) {
+            Ok(bytes) => Some(serde_json::from_slice(&bytes).map_err(|e| ToposhieldError::CorruptState {
+                path: manifest_path.clone(),
+                reason: e.to_string(),
+            })?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(source) => {
+                return Err(ToposhieldError::Io {
+                    operation: "read prover state manifest",
+                    path: manifest_path,
+                    source,
+                }
+                .into())
+            }
+        };
+
+        let config = Self::load_circom_config()?;
+        match manifest {
+            Some(m)
+                if m.fingerprint == fingerprint
+                    && m.aux_offset == config.aux_offset
+                    && !m.params_sha256.is_empty()
+                    && !m.pk_sha256.is_empty() =>
+            {
+                let corrupt = |path: &Path, e: &dyn std::fmt::Display| ToposhieldError::CorruptState {
+                    path: path.to_path_buf(),
+                    reason: e.to_string(),
+                };
+                let params_path = dir.join(STATE_PARAMS_FILE);
+                let params_bytes = read_state_file(&params_path, &m.params_sha256)?;
+                let params = ParamsKZG::<Bn256>::read(&mut Cursor::new(params_bytes))
+                    .map_err(|e| corrupt(&params_path, &e))?;
+                check_params_fit(&config.r1cs, &params)?;
+
+                let pk_path = dir.join(STATE_PK_FILE);
+                let pk_bytes = read_state_file(&pk_path, &m.pk_sha256)?;
+                let pk = ProvingKey::read::<_, CircomCircuit<Bn256>>(&mut Cursor::new(pk_bytes), SerdeFormat::RawBytes)
+                    .map_err(|e| corrupt(&pk_path, &e))?;
+                Self::from_parts(config, params, pk)
+            }
+            _ => {
+                eprintln!("⚠️  Prover state in {} is missing or stale — regenerating...", dir.display());
+                let prover = Self::new()?;
+                prover.save_state(dir)?;
+                Ok(prover)
+            }
+        }
+    }
+
+    /// Load an explicit wire mapping (JSON array: witness index → R1CS wire index),
+    /// e.g. exported alongside a circuit compiled with optimizations
+    pub fn with_wire_mapping(mut self, path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = path.as_ref();
+        let mapping: Vec<usize> =
+            serde_json::from_slice(&with_context(fs::read(path), "read wire mapping", path)?)?;
+        if mapping.len() != self.r1cs.num_variables {
+            return Err(ToposhieldError::WireMappingMismatch {
+                r1cs_wires: self.r1cs.num_variables,
+                witness_wires: mapping.len(),
+            }
+            .into());
+        }
+        self.wire_mapping = Some(mapping);
+        Ok(self)
+    }
+
+    /// Accept proofs whose desc_M matches any version registered in `compat`
+    /// (e.g. old and new descriptors during a protocol upgrade)
+    pub fn with_descriptor_compat(mut self, compat: DescriptorCompat) -> Self {
+        self.descriptor_compat = compat;
+        self
+    }
+
+    /// Only accept proofs whose desc_M is one of `descs` (e.g. the production and
+    /// staging manifolds), rejecting any other with `UnacceptedManifold`. This is an
+    /// authorization check on top of the descriptor version registry.
+    pub fn with_accepted_manifolds(mut self, descs: Vec<[Fr; 4]>) -> Self {
+        self.accepted_manifolds = Some(descs);
+        self
+    }
+
+    /// Fix the number of proofs per rayon task in `verify_many` (at least 1)
+    pub fn with_batch_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.batch_chunk_size = Some(chunk_size.max(1));
+        self
+    }
+
+    /// h_pub and h_sig must be SL(2) holonomies of non-empty reduced words: det = 1 (which
+    /// rules out the zero matrix and every other singular one) and not the identity.
+    /// Costs a few field multiplications, so it runs before any pairing.
+    fn check_public_holonomies(h_pub: [Fr; 4], h_sig: [Fr; 4]) -> Result<(), ToposhieldError> {
+        let identity = [Fr::one(), Fr::zero(), Fr::zero(), Fr::one()];
+        for (component, h) in [("h_pub", h_pub), ("h_sig", h_sig)] {
+            if h[0] * h[3] - h[1] * h[2] != Fr::one() {
+                return Err(ToposhieldError::InvalidPublicInputs { component, reason: "det ≠ 1" });
+            }
+            if h == identity {
+                return Err(ToposhieldError::InvalidPublicInputs { component, reason: "identity" });
+            }
+        }
+        Ok(())
+    }
+
+    /// desc_M must be a registered descriptor version and, if pinned, an accepted manifold
+    fn check_descriptor(&self, desc_m: [Fr; 4]) -> Result<(), ToposhieldError> {
+        check_descriptor_policy(&self.descriptor_compat, self.accepted_manifolds.as_deref(), desc_m)
+    }
+
+    /// Replace the public-input layout; it must match the circuit's public-input count
+    pub fn with_instance_layout(mut self, layout: InstanceLayout) -> Result<Self, ToposhieldError> {
+        layout.validate(self.r1cs.num_inputs - 1)?;
+        self.instance_layout = layout;
+        Ok(self)
+    }
+
+    /// Verifying key, for `verify_minimal` on a machine without the SRS
+    pub fn verifying_key(&self) -> &VerifyingKey<G1Affine> {
+        &self.vk
+    }
+
+    /// The SRS elements `verify_minimal` needs, extracted from the loaded params
+    pub fn minimal_verifier_params(&self) -> MinimalVerifierParams {
+        MinimalVerifierParams::from_params(&self.params)
+    }
+
+    /// Hex SHA-256 of the serialized verifying key. Proofs only verify under the setup
+    /// they were made with, so a bundle records this to name its setup.
+    pub fn setup_fingerprint(&self) -> String {
+        hex::encode(Sha256::digest(self.vk.to_bytes(SerdeFormat::RawBytes)))
+    }
+
+    /// A standalone verifier with this prover's verifying key and policy but only the
+    /// minimal SRS elements; see `TopoShieldVerifier`
+    pub fn verifier(&self) -> TopoShieldVerifier {
+        TopoShieldVerifier {
+            params: self.minimal_verifier_params().to_params(),
+            vk: self.vk.clone(),
+            poseidon_spec_id: self.poseidon_spec_id,
+            instance_layout: self.instance_layout.clone(),
+            descriptor_compat: self.descriptor_compat.clone(),
+            accepted_manifolds: self.accepted_manifolds.clone(),
+        }
+    }
+
+    pub fn instance_layout(&self) -> &InstanceLayout {
+        &self.instance_layout
+    }
+
+    /// Constraint and wire counts of the loaded R1CS
+    pub fn r1cs_summary(&self) -> R1csSummary {
+        R1csSummary {
+            num_constraints: self.r1cs.constraints.len(),
+            num_public_inputs: self.r1cs.num_inputs - 1,
+            num_private_wires: self.r1cs.num_variables - self.r1cs.num_inputs,
+            num_variables: self.r1cs.num_variables,
+            aux_offset: self.aux_offset,
+        }
+    }
+
+    /// Fingerprint of the Poseidon parameters this prover was built with
+    pub fn poseidon_spec_id(&self) -> Fr {
+        self.poseidon_spec_id
+    }
+
+    /// Expected proof length in bytes, derived from the circuit's constraint system.
+    ///
+    /// A SHPLONK transcript holds one 32-byte G1 commitment per advice column, three
+    /// per lookup, one per permutation chunk, one random and `degree - 1` quotient
+    /// pieces for the vanishing argument and two for the multiopen; plus one 32-byte
+    /// evaluation per advice/fixed query, per permutation column, three per permutation
+    /// chunk, five per lookup and one for the vanishing random polynomial. The range
+    /// allows ±25% around that count for backend-specific framing.
+    pub fn expected_proof_size(&self) -> Range<usize> {
+        let cs = self.vk.cs();
+        let degree = cs.degree();
+        let lookups = cs.lookups().len();
+        let permutation_columns = cs.permutation().get_columns().len();
+        let chunk_len = degree.saturating_sub(2).max(1);
+        let permutation_chunks = permutation_columns.div_ceil(chunk_len);
+
+        let commitments = cs.num_advice_columns()
+            + 3 * lookups
+            + permutation_chunks
+            + 1
+            + degree.saturating_sub(1)
+            + 2;
+        let evaluations = cs.advice_queries().len()
+            + cs.fixed_queries().len()
+            + permutation_columns
+            + 3 * permutation_chunks
+            + 5 * lookups
+            + 1;
+
+        let estimate = 32 * (commitments + evaluations);
+        estimate * 3 / 4..estimate * 5 / 4 + 1
+    }
+
     /// Генерирует ZK-доказательство для заданного свидетельства
-    pub fn prove(&self, witness: Witness) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    pub fn prove(&self, witness: Witness) -> Result<Proof, Box<dyn std::error::Error>> {
+        self.prove_with_rng(witness, &mut rand::thread_rng())
+    }
+
+    /// Prove `witness` and bundle the proof with its h_sig, for `verify_message`
+    pub fn sign(&self, witness: Witness) -> Result<Signature, Box<dyn std::error::Error>> {
+        let h_sig = witness.h_sig;
+        let proof = self.prove(witness)?;
+        Ok(Signature { proof, h_sig })
+    }
+
+    /// Same as `prove` with explicit options (e.g. skipping the MockProver pass)
+    pub fn prove_with_options(
+        &self,
+        witness: Witness,
+        options: ProverOptions,
+    ) -> Result<Proof, Box<dyn std::error::Error>> {
+        self.prove_phases(witness, options, &mut rand::thread_rng())
+            .map(|(proof, _)| proof)
+    }
+
+    /// Same as `prove`, but draws the zero-knowledge blinding factors from `rng`.
+    ///
+    /// The randomness only provides zero-knowledge (soundness does not depend on it),
+    /// so a seeded CSPRNG is acceptable where OS entropy is unavailable. The seed must
+    /// be secret and never reused: repeated blinding across proofs can leak the witness.
+    pub fn prove_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        witness: Witness,
+        rng: &mut R,
+    ) -> Result<Proof, Box<dyn std::error::Error>> {
+        self.prove_phases(witness, ProverOptions::default(), rng)
+            .map(|(proof, _)| proof)
+    }
+
+    /// Reproducible proof: the blinding factors come from a ChaCha20Rng seeded with
+    /// `rng_seed`, so the same witness and seed give byte-identical proofs (useful for
+    /// golden files and deduplication).
+    ///
+    /// The seed must be secret and unique per witness. Reusing it for two different
+    /// witnesses reuses the blinding and can leak the private paths.
+    pub fn prove_deterministic(
+        &self,
+        witness: Witness,
+        rng_seed: [u8; 32],
+    ) -> Result<Proof, Box<dyn std::error::Error>> {
+        self.prove_with_rng(witness, &mut ChaCha20Rng::from_seed(rng_seed))
+    }
+
+    /// Same as `prove`, additionally returning a per-phase timing breakdown
+    pub fn prove_timed(
+        &self,
+        witness: Witness,
+    ) -> Result<(Proof, ProveMetrics), Box<dyn std::error::Error>> {
+        self.prove_phases(witness, ProverOptions::default(), &mut rand::thread_rng())
+    }
+
+    fn prove_phases<R: RngCore + CryptoRng>(
+        &self,
+        witness: Witness,
+        options: ProverOptions,
+        rng: &mut R,
+    ) -> Result<(Proof, ProveMetrics), Box<dyn std::error::Error>> {
+        let start = Instant::now();
+        let (circuit, instances) = self.build_circuit(&witness)?;
+        let witness_map_time = start.elapsed();
+
+        // Mock-верификация (для отладки)
+        let phase = Instant::now();
+        if options.run_mock_prover {
+            if let Err(failures) = self.run_mock_prover(&circuit, &instances)? {
+                return Err(format!("Mock prover failed — check witness or circuit: {:?}", failures).into());
+            }
+        }
+        let mock_prover_time = phase.elapsed();
+
+        // Генерация реального доказательства
+        let phase = Instant::now();
+        let proof = self.create_real_proof(circuit, &instances, rng)?;
+        let create_proof_time = phase.elapsed();
+        // The size range is an estimate, so a miss is reported rather than fatal
+        let expected_size = self.expected_proof_size();
+        if !expected_size.contains(&proof.bytes.len()) {
+            eprintln!(
+                "⚠️  Proof of {} bytes is outside the expected range {:?}",
+                proof.bytes.len(),
+                expected_size
+            );
+        }
+
+        let metrics = ProveMetrics {
+            witness_map: witness_map_time,
+            mock_prover: mock_prover_time,
+            create_proof: create_proof_time,
+            total: start.elapsed(),
+        };
+        Ok((proof, metrics))
+    }
+
+    /// Circom witness calculation and instance assembly for a witness
+    pub(crate) fn build_circuit(
+        &self,
+        witness: &Witness,
+    ) -> Result<(CircomCircuit<Bn256>, Vec<Vec<Fr>>), Box<dyn std::error::Error>> {
+        // A witness for another manifold would still prove, but against the wrong system
+        if witness.desc_m != self.desc_m {
+            return Err(ToposhieldError::DescriptorMismatch {
+                expected: self.desc_m,
+                found: witness.desc_m,
+            }
+            .into());
+        }
+
+        // Holonomies must lie in SL(2); the circuit would reject them only after
+        // witness calculation and the mock/real proving passes
+        for (component, h) in [("h_pub", witness.h_pub), ("h_sig", witness.h_sig)] {
+            if h[0] * h[3] - h[1] * h[2] != Fr::one() {
+                return Err(ToposhieldError::NonSl2Holonomy { component }.into());
+            }
+        }
+
         // Подготовка входов для Circom
-        let mut witness_map = witness.to_circom_input();
+        let mut witness_map = witness.to_circom_input()?;
         let witness_vec = CircomCircuit::construct_witness_from_map(
             &self.r1cs,
             &mut witness_map,
             self.aux_offset,
         )?;
 
+        // Without a mapping the WASM witness must line up 1:1 with the R1CS wires;
+        // otherwise the assignment would be silently wrong
+        if self.wire_mapping.is_none() && witness_vec.len() != self.r1cs.num_variables {
+            return Err(ToposhieldError::WireMappingMismatch {
+                r1cs_wires: self.r1cs.num_variables,
+                witness_wires: witness_vec.len(),
+            }
+            .into());
+        }
+
         // Схема со свидетельством
         let circuit = CircomCircuit {
             r1cs: self.r1cs.clone(),
             witness: Some(witness_vec),
-            wire_mapping: None,
+            wire_mapping: self.wire_mapping.clone(),
             aux_offset: self.aux_offset,
         };
 
-        // Публичные входы: H_pub, H_sig, desc_M, m_hash → 16 элементов
-        let instances = vec![vec![
-            witness.h_pub[0], witness.h_pub[1], witness.h_pub[2], witness.h_pub[3],
-            witness.h_sig[0], witness.h_sig[1], witness.h_sig[2], witness.h_sig[3],
-            witness.desc_m[0], witness.desc_m[1], witness.desc_m[2], witness.desc_m[3],
-            witness.m_hash[0], witness.m_hash[1], witness.m_hash[2], witness.m_hash[3],
-        ]];
+        // Публичные входы в порядке InstanceLayout (по умолчанию H_pub, H_sig, desc_M, m_hash)
+        let instances = self.instance_layout.instances(&PublicInputs::from(witness));
+        Ok((circuit, instances))
+    }
 
-        // Mock-верификация (для отладки)
-        let mock_prover = MockProver::run(17, &circuit, instances.clone())?;
-        assert_eq!(
-            mock_prover.verify(),
-            Ok(()),
-            "Mock prover failed — check witness or circuit"
-        );
+    /// Run the MockProver; the outer error is a setup failure, the inner one lists
+    /// the unsatisfied constraints
+    pub(crate) fn run_mock_prover(
+        &self,
+        circuit: &CircomCircuit<Bn256>,
+        instances: &[Vec<Fr>],
+    ) -> Result<Result<(), Vec<VerifyFailure>>, Box<dyn std::error::Error>> {
+        let mock_prover = MockProver::run(self.params.k(), circuit, instances.to_vec())?;
+        Ok(mock_prover.verify())
+    }
 
-        // Генерация реального доказательства
+    /// Real KZG proof generation with the cached proving key
+    pub(crate) fn create_real_proof<R: RngCore + CryptoRng>(
+        &self,
+        circuit: CircomCircuit<Bn256>,
+        instances: &[Vec<Fr>],
+        rng: &mut R,
+    ) -> Result<Proof, Box<dyn std::error::Error>> {
+        self.create_multi_proof(vec![circuit], &[instances], rng)
+    }
+
+    /// One transcript proving several circuit instances at once
+    fn create_multi_proof<R: RngCore + CryptoRng>(
+        &self,
+        circuits: Vec<CircomCircuit<Bn256>>,
+        instances: &[&[Vec<Fr>]],
+        rng: &mut R,
+    ) -> Result<Proof, Box<dyn std::error::Error>> {
         let mut transcript = Blake2bWrite::<_, G1Affine, Challenge255<_>>::init(vec![]);
         create_proof::<
             KZGCommitmentScheme<Bn256>,
@@ -125,45 +1289,290 @@ impl TopoShieldProver {
         >(
             &self.params,
             &self.pk,
-            &[circuit],
-            &[&instances],
-            &mut rand::thread_rng(),
+            &circuits,
+            instances,
+            rng,
             &mut transcript,
         )?;
 
-        Ok(transcript.finalize())
+        Ok(Proof {
+            bytes: transcript.finalize(),
+            poseidon_spec_id: self.poseidon_spec_id,
+        })
     }
 
     /// Верифицирует доказательство
+    /// Proofs produced under a different Poseidon spec are rejected with
+    /// `ToposhieldError::PoseidonSpecMismatch`, and a desc_M outside the accepted
+    /// descriptor versions with `UnsupportedDescriptor`, and an h_pub or h_sig that is
+    /// not a non-identity SL(2) matrix with `InvalidPublicInputs`, before any pairing work.
     pub fn verify(
         &self,
-        proof: &[u8],
+        proof: &Proof,
         h_pub: [Fr; 4],
         h_sig: [Fr; 4],
         desc_m: [Fr; 4],
         m_hash: [Fr; 4],
-    ) -> Result<bool, Error> {
-        let instances = vec![vec![
-            h_pub[0], h_pub[1], h_pub[2], h_pub[3],
-            h_sig[0], h_sig[1], h_sig[2], h_sig[3],
-            desc_m[0], desc_m[1], desc_m[2], desc_m[3],
-            m_hash[0], m_hash[1], m_hash[2], m_hash[3],
-        ]];
-
-        let strategy = AccumulatorStrategy::new(&self.params);
-        let mut transcript = Blake2bRead::<_, G1Affine, Challenge255<_>>::init(proof);
-        let result = verify_proof::<
-            KZGCommitmentScheme<Bn256>,
-            halo2_proofs::poly::kzg::multiopen::VerifierSHPLONK<_>,
-            Challenge255<_>,
-            AccumulatorStrategy<_>,
-            _,
-            Blake2bRead<_, _, _>,
-        >(&self.params, &self.vk, strategy, &[instances.as_slice()], &mut transcript);
+    ) -> Result<bool, ToposhieldError> {
+        if proof.poseidon_spec_id != self.poseidon_spec_id {
+            return Err(ToposhieldError::PoseidonSpecMismatch {
+                expected: self.poseidon_spec_id,
+                found: proof.poseidon_spec_id,
+            });
+        }
 
-        match result {
-            Ok(()) => Ok(true),
-            Err(_) => Ok(false),
+        Self::check_public_holonomies(h_pub, h_sig)?;
+        self.check_descriptor(desc_m)?;
+
+        let instances = self.instance_layout.instances(&PublicInputs { h_pub, h_sig, desc_m, m_hash });
+        Ok(self.verify_instances(proof, &[instances.as_slice()])? == VerifyOutcome::Valid)
+    }
+
+    /// `verify`, but first require `proof.transcript_hash() == expected_hash`.
+    /// A proof that differs from the committed one is rejected even if it is valid
+    /// for the same public inputs (e.g. a re-randomized proof), without pairing work.
+    pub fn verify_with_commitment(
+        &self,
+        proof: &Proof,
+        expected_hash: [u8; 32],
+        h_pub: [Fr; 4],
+        h_sig: [Fr; 4],
+        desc_m: [Fr; 4],
+        m_hash: [Fr; 4],
+    ) -> Result<bool, ToposhieldError> {
+        if proof.transcript_hash() != expected_hash {
+            return Ok(false);
         }
+        self.verify(proof, h_pub, h_sig, desc_m, m_hash)
+    }
+
+    /// High-level verification from trusted components only: m_hash is re-derived from
+    /// `message`, h_pub and desc_m come from `public_key` and h_sig from the signature.
+    /// Any error (foreign Poseidon spec, unsupported descriptor) counts as invalid.
+    pub fn verify_message(&self, public_key: &PublicKey, message: &[u8], signature: &Signature) -> bool {
+        self.verify(
+            &signature.proof,
+            public_key.h_pub,
+            signature.h_sig,
+            public_key.desc_m,
+            Witness::hash_to_4fr(message),
+        )
+        .unwrap_or(false)
+    }
+
+    /// `verify_message` for a signature made with `Witness::new_with_epoch`: m_hash is
+    /// re-derived for `epoch`, so a signature from any other epoch is rejected
+    pub fn verify_message_at_epoch(
+        &self,
+        public_key: &PublicKey,
+        message: &[u8],
+        epoch: u64,
+        signature: &Signature,
+    ) -> bool {
+        self.verify(
+            &signature.proof,
+            public_key.h_pub,
+            signature.h_sig,
+            public_key.desc_m,
+            Witness::epoch_message_hash(message, epoch),
+        )
+        .unwrap_or(false)
+    }
+
+    /// Verify proof bytes read straight from `reader` (a socket, a pipe) without
+    /// buffering them first: halo2 pulls each commitment and evaluation as it needs it,
+    /// and nothing past the proof is consumed. The stream carries only proof bytes, so
+    /// unlike `verify` there is no Poseidon spec id to check; the descriptor policy
+    /// still applies. A truncated stream counts as an invalid proof.
+    pub fn verify_from_reader(&self, reader: impl Read, inputs: &PublicInputs) -> Result<bool, ToposhieldError> {
+        Self::check_public_holonomies(inputs.h_pub, inputs.h_sig)?;
+        self.check_descriptor(inputs.desc_m)?;
+        let instances = self.instance_layout.instances(inputs);
+        Ok(self.verify_transcript(reader, &[instances.as_slice()])? == VerifyOutcome::Valid)
+    }
+
+    /// Verify a bundle from `Proof::from_bundle_json`. A bundle made under another
+    /// setup is rejected with `SetupMismatch`, and one whose desc_m does not belong to
+    /// its recorded descriptor version with `UnsupportedDescriptor`; then `verify`.
+    pub fn verify_bundle(&self, bundle: &ProofBundle) -> Result<bool, ToposhieldError> {
+        let expected = self.setup_fingerprint();
+        if bundle.setup_fingerprint != expected {
+            return Err(ToposhieldError::SetupMismatch { expected, found: bundle.setup_fingerprint.clone() });
+        }
+        let inputs = &bundle.inputs;
+        if self.descriptor_compat.accepts(&inputs.desc_m) != Some(bundle.descriptor_version) {
+            return Err(ToposhieldError::UnsupportedDescriptor { desc_m: inputs.desc_m });
+        }
+        self.verify(&bundle.proof, inputs.h_pub, inputs.h_sig, inputs.desc_m, inputs.m_hash)
+    }
+
+    /// Verify against a raw instance column, e.g. one assembled by an external encoder.
+    /// No descriptor policy is applied; a column whose length differs from the circuit's
+    /// public-input count is rejected with `InstanceCountMismatch`.
+    pub fn verify_instance(&self, proof: &Proof, instance: &[Fr]) -> Result<bool, ToposhieldError> {
+        if proof.poseidon_spec_id != self.poseidon_spec_id {
+            return Err(ToposhieldError::PoseidonSpecMismatch {
+                expected: self.poseidon_spec_id,
+                found: proof.poseidon_spec_id,
+            });
+        }
+        let instances = vec![instance.to_vec()];
+        Ok(self.verify_instances(proof, &[instances.as_slice()])? == VerifyOutcome::Valid)
+    }
+
+    /// Like `verify`, but reports why a proof was rejected. On a pairing failure the
+    /// proof is re-checked with pairs of instance components swapped, so a proof that
+    /// is valid under a different public-input order is reported as `InstanceMismatch`.
+    /// That diagnosis costs up to one extra pairing check per swap (six for the four
+    /// components), so rejecting a bad proof here is several times slower than `verify`;
+    /// use it to debug an integration, not on a hot path.
+    pub fn verify_detailed(
+        &self,
+        proof: &Proof,
+        h_pub: [Fr; 4],
+        h_sig: [Fr; 4],
+        desc_m: [Fr; 4],
+        m_hash: [Fr; 4],
+    ) -> Result<VerifyOutcome, ToposhieldError> {
+        if proof.poseidon_spec_id != self.poseidon_spec_id {
+            return Err(ToposhieldError::PoseidonSpecMismatch {
+                expected: self.poseidon_spec_id,
+                found: proof.poseidon_spec_id,
+            });
+        }
+
+        Self::check_public_holonomies(h_pub, h_sig)?;
+        self.check_descriptor(desc_m)?;
+
+        let inputs = PublicInputs { h_pub, h_sig, desc_m, m_hash };
+        let instances = self.instance_layout.instances(&inputs);
+        let outcome = self.verify_instances(proof, &[instances.as_slice()])?;
+        if outcome != VerifyOutcome::InvalidPairing {
+            return Ok(outcome);
+        }
+
+        for layout in self.instance_layout.swapped() {
+            let reordered = layout.instances(&inputs);
+            if self.verify_instances(proof, &[reordered.as_slice()])? == VerifyOutcome::Valid {
+                return Ok(VerifyOutcome::InstanceMismatch);
+            }
+        }
+        Ok(VerifyOutcome::InvalidPairing)
+    }
+
+    /// Prove several signatures under one public key in a single proof.
+    /// All witnesses must share h_pub and desc_m; the circuit is replicated once per
+    /// witness inside one transcript, so the proof commits to every (h_sig, m_hash).
+    pub fn prove_batch(&self, witnesses: &[Witness]) -> Result<Proof, Box<dyn std::error::Error>> {
+        self.prove_batch_with_options(witnesses, ProverOptions::default())
+    }
+
+    /// Same as `prove_batch` with explicit options; the MockProver pass, if enabled,
+    /// runs once per witness
+    pub fn prove_batch_with_options(
+        &self,
+        witnesses: &[Witness],
+        options: ProverOptions,
+    ) -> Result<Proof, Box<dyn std::error::Error>> {
+        let first = witnesses.first().ok_or("prove_batch requires at least one witness")?;
+        if witnesses
+            .iter()
+            .any(|w| w.h_pub != first.h_pub || w.desc_m != first.desc_m)
+        {
+            return Err(ToposhieldError::BatchKeyMismatch.into());
+        }
+
+        let mut circuits = Vec::with_capacity(witnesses.len());
+        let mut instances = Vec::with_capacity(witnesses.len());
+        for (i, witness) in witnesses.iter().enumerate() {
+            let (circuit, instance) = self.build_circuit(witness)?;
+            if options.run_mock_prover {
+                if let Err(failures) = self.run_mock_prover(&circuit, &instance)? {
+                    return Err(format!("Mock prover failed for witness {}: {:?}", i, failures).into());
+                }
+            }
+            circuits.push(circuit);
+            instances.push(instance);
+        }
+
+        let instance_refs: Vec<&[Vec<Fr>]> = instances.iter().map(|i| i.as_slice()).collect();
+        self.create_multi_proof(circuits, &instance_refs, &mut rand::thread_rng())
+    }
+
+    /// Verify a `prove_batch` proof against the shared key and every (h_sig, m_hash),
+    /// in the same order as the witnesses were proven. Inputs are checked as in `verify`.
+    pub fn verify_batch(
+        &self,
+        proof: &Proof,
+        h_pub: [Fr; 4],
+        desc_m: [Fr; 4],
+        signatures: &[([Fr; 4], [Fr; 4])],
+    ) -> Result<bool, ToposhieldError> {
+        if proof.poseidon_spec_id != self.poseidon_spec_id {
+            return Err(ToposhieldError::PoseidonSpecMismatch {
+                expected: self.poseidon_spec_id,
+                found: proof.poseidon_spec_id,
+            });
+        }
+        for &(h_sig, _) in signatures {
+            Self::check_public_holonomies(h_pub, h_sig)?;
+        }
+        self.check_descriptor(desc_m)?;
+
+        let instances: Vec<Vec<Vec<Fr>>> = signatures
+            .iter()
+            .map(|&(h_sig, m_hash)| self.instance_layout.instances(&PublicInputs { h_pub, h_sig, desc_m, m_hash }))
+            .collect();
+        let instance_refs: Vec<&[Vec<Fr>]> = instances.iter().map(|i| i.as_slice()).collect();
+        Ok(self.verify_instances(proof, &instance_refs)? == VerifyOutcome::Valid)
+    }
+
+    /// Verify independent proofs in parallel, `batch_chunk_size` proofs per rayon task.
+    /// Results are in request order, each as `verify` would return it.
+    pub fn verify_many(&self, requests: &[VerifyRequest<'_>]) -> Vec<Result<bool, ToposhieldError>> {
+        let chunk_size = self
+            .batch_chunk_size
+            .unwrap_or_else(|| default_batch_chunk_size(requests.len()));
+        requests
+            .par_chunks(chunk_size)
+            .flat_map_iter(|chunk| {
+                chunk
+                    .iter()
+                    .map(|r| self.verify(r.proof, r.h_pub, r.h_sig, r.desc_m, r.m_hash))
+            })
+            .collect()
+    }
+
+    /// KZG/SHPLONK verification of a transcript against one instance set per circuit.
+    /// halo2 errors are classified into outcomes; unexpected ones are passed through.
+    ///
+    /// The strategy is built fresh per call on purpose: `verify_proof` consumes it and
+    /// accumulates this proof's MSM into it, so it cannot be cached or shared between
+    /// verifications. Construction only borrows the cached params and is negligible
+    /// next to the pairing (see `benches/verify_bench.rs`).
+    fn verify_instances(
+        &self,
+        proof: &Proof,
+        instances: &[&[Vec<Fr>]],
+    ) -> Result<VerifyOutcome, ToposhieldError> {
+        self.verify_transcript(proof.bytes.as_slice(), instances)
+    }
+
+    /// `verify_instances` reading the proof transcript from any reader
+    fn verify_transcript<R: Read>(
+        &self,
+        reader: R,
+        instances: &[&[Vec<Fr>]],
+    ) -> Result<VerifyOutcome, ToposhieldError> {
+        // Layout drift between this code and the circuit must not surface as an
+        // opaque halo2 error
+        let expected = self.r1cs.num_inputs - 1;
+        for column in instances.iter().flat_map(|columns| columns.iter()) {
+            if column.len() != expected {
+                return Err(ToposhieldError::InstanceCountMismatch { expected, got: column.len() });
+            }
+        }
+
+        verify_with_params(&self.params, &self.vk, reader, instances)
     }
 }
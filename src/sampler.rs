@@ -0,0 +1,70 @@
+// src/sampler.rs
+// Policies mapping one PRF output (a Poseidon hash) to a generator index
+// Witness generation uses UniformModSampler; alternatives are for experimentation
+use crate::{manifold::NUM_GENERATOR_INDICES, witness::fr_to_u64_le};
+use ff::PrimeField;
+use halo2_proofs::halo2curves::bn256::Fr;
+
+/// Maps a PRF output to a generator index in 0–19. Must be deterministic.
+pub trait PathSampler {
+    fn sample_index(&self, hash: Fr) -> u8;
+}
+
+/// Low 64 bits mod 20 — the sampler `Witness::new` uses. The bias is at most
+/// 2⁶⁴ mod 20 / 2⁶⁴ ≈ 2⁻⁶², negligible in practice.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UniformModSampler;
+
+impl PathSampler for UniformModSampler {
+    fn sample_index(&self, hash: Fr) -> u8 {
+        (fr_to_u64_le(&hash) % NUM_GENERATOR_INDICES as u64) as u8
+    }
+}
+
+/// Exactly uniform: scans the repr bytes and accepts the first below 240 = 12·20,
+/// returning it mod 20. All 32 bytes are rejected with probability (16/256)³² ≈ 2⁻¹²⁸,
+/// in which case it falls back to `UniformModSampler`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RejectionSampler;
+
+impl PathSampler for RejectionSampler {
+    fn sample_index(&self, hash: Fr) -> u8 {
+        const LIMIT: u8 = (256 / NUM_GENERATOR_INDICES * NUM_GENERATOR_INDICES) as u8;
+        hash.to_repr()
+            .as_ref()
+            .iter()
+            .find(|&&byte| byte < LIMIT)
+            .map(|&byte| byte % NUM_GENERATOR_INDICES as u8)
+            .unwrap_or_else(|| UniformModSampler.sample_index(hash))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples<S: PathSampler>(sampler: &S) -> Vec<u8> {
+        (0..500u64).map(|i| sampler.sample_index(Fr::from(i * 7919 + 13))).collect()
+    }
+
+    #[test]
+    fn test_uniform_mod_sampler() {
+        let out = samples(&UniformModSampler);
+        assert!(out.iter().all(|&idx| idx < 20));
+        assert_eq!(out, samples(&UniformModSampler));
+        assert_eq!(UniformModSampler.sample_index(Fr::from(45)), 5);
+    }
+
+    #[test]
+    fn test_rejection_sampler() {
+        let out = samples(&RejectionSampler);
+        assert!(out.iter().all(|&idx| idx < 20));
+        assert_eq!(out, samples(&RejectionSampler));
+        // First byte 250 is rejected, second byte 41 is accepted: 41 mod 20 = 1
+        assert_eq!(RejectionSampler.sample_index(Fr::from(250 + 41 * 256)), 1);
+        // Every index is reachable
+        for idx in 0..20u8 {
+            assert!(out.contains(&idx), "index {} never sampled", idx);
+        }
+    }
+}
@@ -0,0 +1,113 @@
+// src/secret.rs
+// Loading of the private signing seed from the environment or a keyfile
+// The seed lives in a zeroizing buffer and well-known example seeds are refused
+use crate::error::ToposhieldError;
+use std::path::Path;
+use zeroize::Zeroizing;
+
+/// Seeds that appear in examples, tests and docs; never acceptable as real keys
+const PLACEHOLDER_SEEDS: &[&[u8]] = &[
+    b"",
+    b"secure_seed",
+    b"example_seed_2025",
+    b"my_secret_seed_2025",
+    b"integration_test_seed_2025",
+    b"seed",
+    b"secret",
+    b"changeme",
+];
+
+/// Private signing seed, wiped from memory on drop
+pub struct SecretSeed(Zeroizing<Vec<u8>>);
+
+impl SecretSeed {
+    /// Read the seed from environment variable `var`
+    pub fn from_env(var: &str) -> Result<Self, ToposhieldError> {
+        Self::from_lookup(var, |name| std::env::var(name))
+    }
+
+    /// Like `from_env`, with variables read through `lookup` instead of the process
+    /// environment (e.g. a config map, or a fixed source in tests)
+    pub fn from_lookup(
+        var: &str,
+        lookup: impl FnOnce(&str) -> Result<String, std::env::VarError>,
+    ) -> Result<Self, ToposhieldError> {
+        let value = lookup(var).map_err(|e| ToposhieldError::SeedUnavailable {
+            source: format!("environment variable {}", var),
+            reason: e.to_string(),
+        })?;
+        Self::checked(value.into_bytes())
+    }
+
+    /// Read the seed from a keyfile; a trailing newline is stripped
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ToposhieldError> {
+        let path = path.as_ref();
+        let mut bytes = std::fs::read(path).map_err(|e| ToposhieldError::SeedUnavailable {
+            source: format!("keyfile {}", path.display()),
+            reason: e.to_string(),
+        })?;
+        while matches!(bytes.last(), Some(b'\n') | Some(b'\r')) {
+            bytes.pop();
+        }
+        Self::checked(bytes)
+    }
+
+    /// True if the seed is a well-known example value
+    pub fn is_placeholder(seed: &[u8]) -> bool {
+        PLACEHOLDER_SEEDS.contains(&seed)
+    }
+
+    /// Seed bytes, for passing to `Witness::new`
+    pub fn expose(&self) -> &[u8] {
+        &self.0
+    }
+
+    fn checked(bytes: Vec<u8>) -> Result<Self, ToposhieldError> {
+        let seed = Self(Zeroizing::new(bytes));
+        if Self::is_placeholder(seed.expose()) {
+            return Err(ToposhieldError::PlaceholderSeed);
+        }
+        Ok(seed)
+    }
+}
+
+impl std::fmt::Debug for SecretSeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SecretSeed(<{} bytes redacted>)", self.0.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A one-variable environment, so tests never touch the process environment
+    fn env_with(name: &'static str, value: &'static str) -> impl Fn(&str) -> Result<String, std::env::VarError> {
+        move |var| if var == name { Ok(value.to_string()) } else { Err(std::env::VarError::NotPresent) }
+    }
+
+    #[test]
+    fn test_placeholder_seed_rejected() {
+        let result = SecretSeed::from_lookup("TOPOSHIELD_SEED", env_with("TOPOSHIELD_SEED", "example_seed_2025"));
+        assert!(matches!(result, Err(ToposhieldError::PlaceholderSeed)));
+        assert!(SecretSeed::is_placeholder(b"secure_seed"));
+    }
+
+    #[test]
+    fn test_seed_from_env_and_file() {
+        let env = env_with("TOPOSHIELD_SEED", "f3a9c1d27be84e06");
+        let seed = SecretSeed::from_lookup("TOPOSHIELD_SEED", &env).unwrap();
+        assert_eq!(seed.expose(), b"f3a9c1d27be84e06");
+
+        let mut keyfile = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut keyfile, b"f3a9c1d27be84e06\n").unwrap();
+        let seed = SecretSeed::from_file(keyfile.path()).unwrap();
+        assert_eq!(seed.expose(), b"f3a9c1d27be84e06");
+
+        assert!(matches!(
+            SecretSeed::from_lookup("TOPOSHIELD_UNSET_SEED", &env),
+            Err(ToposhieldError::SeedUnavailable { .. })
+        ));
+        assert!(!format!("{:?}", seed).contains("f3a9"));
+    }
+}
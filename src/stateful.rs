@@ -0,0 +1,285 @@
+// src/stateful.rs
+// Stateful signing mode: one fresh gamma per message, keys committed in a Merkle tree
+// gamma_i is derived from seed || i; verifiers hold only the tree root
+use crate::{
+    error::ToposhieldError,
+    poseidon_widths::{MERKLE_RATE, MERKLE_T},
+    witness::Witness,
+};
+use ff::Field;
+use halo2_proofs::halo2curves::bn256::Fr;
+use poseidon::{PoseidonHasher, Spec};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use zeroize::Zeroizing;
+
+/// Poseidon Merkle tree over the public keys h_pub_0, ..., h_pub_{n-1}.
+/// Leaves are padded up to a power of two with `padding_leaf()`, which no key hashes
+/// to, so a padded position never verifies as a second index of a real key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PublicKeyTree {
+    /// levels[0] are the (padded) leaf hashes, the last level is the root
+    levels: Vec<Vec<[Fr; 4]>>,
+    num_keys: usize,
+}
+
+/// Sibling hashes from a leaf up to the root
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MerklePath {
+    pub index: usize,
+    pub siblings: Vec<[Fr; 4]>,
+}
+
+impl PublicKeyTree {
+    /// Build the tree over `keys` (at least one)
+    pub fn new(keys: &[[Fr; 4]]) -> Self {
+        assert!(!keys.is_empty(), "PublicKeyTree needs at least one key");
+        let mut leaves: Vec<[Fr; 4]> = keys.iter().map(hash_leaf).collect();
+        leaves.resize(keys.len().next_power_of_two(), padding_leaf());
+
+        let mut levels = vec![leaves];
+        while levels.last().is_some_and(|level| level.len() > 1) {
+            let next = levels
+                .last()
+                .expect("non-empty")
+                .chunks(2)
+                .map(|pair| hash_node(&pair[0], &pair[1]))
+                .collect();
+            levels.push(next);
+        }
+        Self {
+            levels,
+            num_keys: keys.len(),
+        }
+    }
+
+    pub fn root(&self) -> [Fr; 4] {
+        self.levels.last().expect("non-empty")[0]
+    }
+
+    pub fn num_keys(&self) -> usize {
+        self.num_keys
+    }
+
+    /// Membership path for key `index`
+    pub fn path(&self, index: usize) -> Option<MerklePath> {
+        if index >= self.num_keys {
+            return None;
+        }
+        let siblings = self.levels[..self.levels.len() - 1]
+            .iter()
+            .enumerate()
+            .map(|(depth, level)| level[(index >> depth) ^ 1])
+            .collect();
+        Some(MerklePath { index, siblings })
+    }
+}
+
+impl MerklePath {
+    /// True if `h_pub` sits at `self.index` in the tree with the given root
+    pub fn verify(&self, root: [Fr; 4], h_pub: [Fr; 4]) -> bool {
+        let mut node = hash_leaf(&h_pub);
+        for (depth, sibling) in self.siblings.iter().enumerate() {
+            node = if (self.index >> depth) & 1 == 0 {
+                hash_node(&node, sibling)
+            } else {
+                hash_node(sibling, &node)
+            };
+        }
+        self.index >> self.siblings.len() == 0 && node == root
+    }
+}
+
+/// Signer holding `num_keys` one-time keys derived from a single seed.
+/// Each index signs at most one message.
+pub struct StatefulSigner {
+    seed: Zeroizing<Vec<u8>>,
+    tree: PublicKeyTree,
+    /// Indices already signed with. Kept in memory only: callers must persist
+    /// `used_indices()` before releasing a signature and restore them with
+    /// `with_used`, or a restarted signer will reuse a one-time key.
+    used: BTreeSet<usize>,
+}
+
+impl StatefulSigner {
+    /// Derive all public keys and commit them in a `PublicKeyTree`
+    pub fn new(seed: &[u8], num_keys: usize) -> Self {
+        let keys: Vec<[Fr; 4]> = (0..num_keys)
+            .map(|i| Witness::public_key(&Self::key_seed(seed, i)))
+            .collect();
+        Self {
+            seed: Zeroizing::new(seed.to_vec()),
+            tree: PublicKeyTree::new(&keys),
+            used: BTreeSet::new(),
+        }
+    }
+
+    /// `new`, with the indices a previous signer already used marked as spent
+    pub fn with_used(seed: &[u8], num_keys: usize, used: impl IntoIterator<Item = usize>) -> Self {
+        let mut signer = Self::new(seed, num_keys);
+        signer.used.extend(used);
+        signer
+    }
+
+    /// Indices spent so far, in ascending order
+    pub fn used_indices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.used.iter().copied()
+    }
+
+    /// Root to publish as the signer's long-term public key
+    pub fn root(&self) -> [Fr; 4] {
+        self.tree.root()
+    }
+
+    pub fn tree(&self) -> &PublicKeyTree {
+        &self.tree
+    }
+
+    /// Sign `message` with key `index`: the witness uses gamma_index, and the path
+    /// proves its h_pub is in the published tree. An index can only be used once per
+    /// signer instance; see `used` for persisting that across restarts.
+    pub fn sign(&mut self, index: usize, message: &[u8]) -> Result<(Witness, MerklePath), ToposhieldError> {
+        let path = self
+            .tree
+            .path(index)
+            .ok_or(ToposhieldError::KeyIndexUnavailable { index })?;
+        if !self.used.insert(index) {
+            return Err(ToposhieldError::KeyIndexUnavailable { index });
+        }
+        let witness = Witness::new(message, &Self::key_seed(&self.seed, index));
+        Ok((witness, path))
+    }
+
+    /// Seed of the index-th key: len(seed) || seed || i (little-endian u64s). The length
+    /// prefix keeps key i of `seed` apart from the plain key of the seed `seed || i`.
+    fn key_seed(seed: &[u8], index: usize) -> Zeroizing<Vec<u8>> {
+        let mut key_seed = Zeroizing::new(Vec::with_capacity(seed.len() + 16));
+        key_seed.extend_from_slice(&(seed.len() as u64).to_le_bytes());
+        key_seed.extend_from_slice(seed);
+        key_seed.extend_from_slice(&(index as u64).to_le_bytes());
+        key_seed
+    }
+}
+
+/// Check a stateful signature's key against the published root
+pub fn verify_membership(root: [Fr; 4], witness_h_pub: [Fr; 4], path: &MerklePath) -> bool {
+    path.verify(root, witness_h_pub)
+}
+
+fn hash_leaf(h_pub: &[Fr; 4]) -> [Fr; 4] {
+    let mut hasher = PoseidonHasher::<Fr, _, MERKLE_T, MERKLE_RATE>::new(Spec::new());
+    hasher.update(&[Fr::zero()]);
+    hasher.update(h_pub);
+    let result = hasher.squeeze();
+    [result[0], result[1], result[2], result[3]]
+}
+
+/// Leaf of the unused positions up to the next power of two: domain 2, so it is not
+/// `hash_leaf` of any key
+fn padding_leaf() -> [Fr; 4] {
+    let mut hasher = PoseidonHasher::<Fr, _, MERKLE_T, MERKLE_RATE>::new(Spec::new());
+    hasher.update(&[Fr::from(2u64)]);
+    let result = hasher.squeeze();
+    [result[0], result[1], result[2], result[3]]
+}
+
+fn hash_node(left: &[Fr; 4], right: &[Fr; 4]) -> [Fr; 4] {
+    let mut hasher = PoseidonHasher::<Fr, _, MERKLE_T, MERKLE_RATE>::new(Spec::new());
+    hasher.update(&[Fr::one()]);
+    hasher.update(left);
+    hasher.update(right);
+    let result = hasher.squeeze();
+    [result[0], result[1], result[2], result[3]]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_at_several_indices() {
+        let mut signer = StatefulSigner::new(b"stateful_seed", 5);
+        let root = signer.root();
+
+        let mut keys = Vec::new();
+        for index in [0, 3, 4] {
+            let (witness, path) = signer.sign(index, b"Stateful message").unwrap();
+            assert_eq!(path.index, index);
+            assert!(verify_membership(root, witness.h_pub, &path));
+            keys.push(witness.h_pub);
+        }
+        // Every index has its own gamma and public key
+        assert_ne!(keys[0], keys[1]);
+        assert_ne!(keys[1], keys[2]);
+    }
+
+    #[test]
+    fn test_membership_rejects_wrong_key_or_root() {
+        let mut signer = StatefulSigner::new(b"stateful_seed", 4);
+        let (witness, path) = signer.sign(1, b"Stateful message").unwrap();
+        let (other, _) = signer.sign(2, b"Stateful message").unwrap();
+
+        assert!(!verify_membership(signer.root(), other.h_pub, &path));
+        let foreign = StatefulSigner::new(b"other_seed", 4);
+        assert!(!verify_membership(foreign.root(), witness.h_pub, &path));
+
+        let mut moved = path.clone();
+        moved.index = 0;
+        assert!(!verify_membership(signer.root(), witness.h_pub, &moved));
+    }
+
+    #[test]
+    fn test_padded_index_does_not_verify() {
+        // 3 keys are padded to 4 leaves; position 3 must not verify for the last key
+        let mut signer = StatefulSigner::new(b"stateful_seed", 3);
+        let (witness, path) = signer.sign(2, b"Stateful message").unwrap();
+        assert!(verify_membership(signer.root(), witness.h_pub, &path));
+        assert!(signer.tree().path(3).is_none());
+
+        let mut padded = path.clone();
+        padded.index = 3;
+        padded.siblings[0] = signer.tree().levels[0][2];
+        assert!(!verify_membership(signer.root(), witness.h_pub, &padded));
+    }
+
+    #[test]
+    fn test_key_seed_is_length_prefixed() {
+        // Without the prefix, key 1 of "ab" would be the single-key signer of "ab" || 1
+        let mut extended = b"ab".to_vec();
+        extended.extend_from_slice(&1u64.to_le_bytes());
+        assert_ne!(*StatefulSigner::key_seed(b"ab", 1), extended);
+        assert_ne!(
+            StatefulSigner::new(b"ab", 2).tree().levels[0][1],
+            hash_leaf(&Witness::public_key(&extended))
+        );
+    }
+
+    #[test]
+    fn test_with_used_restores_spent_indices() {
+        let mut signer = StatefulSigner::new(b"stateful_seed", 4);
+        signer.sign(1, b"first").unwrap();
+        signer.sign(3, b"second").unwrap();
+
+        let mut restored = StatefulSigner::with_used(b"stateful_seed", 4, signer.used_indices());
+        assert_eq!(restored.used_indices().collect::<Vec<_>>(), vec![1, 3]);
+        assert!(matches!(
+            restored.sign(1, b"again"),
+            Err(ToposhieldError::KeyIndexUnavailable { index: 1 })
+        ));
+        assert!(restored.sign(0, b"fresh").is_ok());
+    }
+
+    #[test]
+    fn test_index_used_once() {
+        let mut signer = StatefulSigner::new(b"stateful_seed", 2);
+        assert!(signer.sign(0, b"first").is_ok());
+        assert!(matches!(
+            signer.sign(0, b"second"),
+            Err(ToposhieldError::KeyIndexUnavailable { index: 0 })
+        ));
+        assert!(matches!(
+            signer.sign(2, b"out of range"),
+            Err(ToposhieldError::KeyIndexUnavailable { index: 2 })
+        ));
+    }
+}
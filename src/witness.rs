@@ -5,9 +5,18 @@
 use ff::{Field, PrimeField};
 use halo2_proofs::halo2curves::bn256::Fr;
 use poseidon::{PoseidonHasher, Spec};
+use rand::{CryptoRng, RngCore};
 use serde::{Deserialize, Serialize};
+use static_assertions::const_assert_eq;
 use std::collections::BTreeMap;
-use crate::manifold::HyperbolicManifold;
+use zeroize::Zeroizing;
+use crate::manifold::{HolonomyGroup, HyperbolicManifold, Sl2Matrix, NUM_GENERATOR_INDICES};
+use crate::hash_to_field::MessageEncoding;
+use crate::sampler::PathSampler;
+use crate::poseidon_widths::{
+    COMMIT_RATE, COMMIT_T, EPOCH_RATE, EPOCH_T, MHASH_RATE, MHASH_T, PATH_RATE, PATH_T, SEED_RATE, SEED_T,
+    SPEC_ID_RATE, SPEC_ID_T,
+};
 
 /// Witness for TopoShield ZKP circuit
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -18,46 +27,298 @@ pub struct Witness {
     pub desc_m: [Fr; 4],
     pub m_hash: [Fr; 4],
     /// Private witness (generator indices 0–19)
-    pub gamma: Vec<u8>,
-    pub delta: Vec<u8>,
+    pub gamma: ReducedPath,
+    pub delta: ReducedPath,
 }
 
-const PATH_LENGTH: usize = 20;
+/// A private path with PATH_LENGTH generator indices, all < 20, and no adjacent
+/// inverse pair. The only constructor validates; deserialization goes through it.
+///
+/// Policy: unreduced input is rejected (`NotReduced`), not reduced. Free reduction
+/// shortens the word, and the circuit needs exactly PATH_LENGTH indices.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(try_from = "Vec<u8>", into = "Vec<u8>")]
+pub struct ReducedPath(Vec<u8>);
+
+impl ReducedPath {
+    pub fn new(path: Vec<u8>) -> Result<Self, WitnessViolation> {
+        Self::new_in(&HyperbolicManifold::new(), path)
+    }
+
+    /// `new` for a path over another holonomy group
+    pub fn new_in<G: HolonomyGroup>(group: &G, path: Vec<u8>) -> Result<Self, WitnessViolation> {
+        Self::check(group, "path", &path)?;
+        Ok(Self(path))
+    }
+
+    pub fn into_inner(self) -> Vec<u8> {
+        self.0
+    }
+
+    /// Every violation of a raw path named `name`, e.g. for triaging imported data:
+    /// wrong length, then each out-of-range index, then each inverse pair
+    pub fn violations(name: &'static str, path: &[u8]) -> Vec<WitnessViolation> {
+        Self::violations_in(&HyperbolicManifold::new(), name, path)
+    }
+
+    /// `violations` against the indices and inverses of `group`
+    pub fn violations_in<G: HolonomyGroup>(group: &G, name: &'static str, path: &[u8]) -> Vec<WitnessViolation> {
+        let mut violations = Vec::new();
+        if path.len() != PATH_LENGTH {
+            violations.push(WitnessViolation::InvalidPathLength { path: name, len: path.len() });
+        }
+        violations.extend(
+            path.iter()
+                .enumerate()
+                .filter(|(_, &idx)| idx as usize >= group.num_generators())
+                .map(|(position, &index)| WitnessViolation::IndexOutOfRange { path: name, position, index }),
+        );
+        violations.extend(
+            path.windows(2)
+                .enumerate()
+                .filter(|(_, pair)| group.is_inverse_pair(pair[0], pair[1]))
+                .map(|(position, _)| WitnessViolation::NotReduced { path: name, position }),
+        );
+        violations
+    }
+
+    /// First violation of a path named `name`
+    fn check<G: HolonomyGroup>(group: &G, name: &'static str, path: &[u8]) -> Result<(), WitnessViolation> {
+        match Self::violations_in(group, name, path).into_iter().next() {
+            Some(violation) => Err(violation),
+            None => Ok(()),
+        }
+    }
+}
+
+impl std::ops::Deref for ReducedPath {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl TryFrom<Vec<u8>> for ReducedPath {
+    type Error = WitnessViolation;
+
+    fn try_from(path: Vec<u8>) -> Result<Self, WitnessViolation> {
+        Self::new(path)
+    }
+}
+
+impl From<ReducedPath> for Vec<u8> {
+    fn from(path: ReducedPath) -> Vec<u8> {
+        path.0
+    }
+}
+
+/// Low 64 bits mod the group's index count; draws the same indices as
+/// `UniformModSampler` over `HyperbolicManifold`
+struct GroupSampler<'a, G>(&'a G);
+
+impl<G: HolonomyGroup> PathSampler for GroupSampler<'_, G> {
+    fn sample_index(&self, hash: Fr) -> u8 {
+        (fr_to_u64_le(&hash) % self.0.num_generators() as u64) as u8
+    }
+}
+
+/// A structural invariant violated by a witness
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WitnessViolation {
+    /// Path (gamma or delta) does not have PATH_LENGTH entries
+    InvalidPathLength { path: &'static str, len: usize },
+    /// Generator index outside 0–19
+    IndexOutOfRange { path: &'static str, position: usize, index: u8 },
+    /// Adjacent inverse pair at (position, position + 1)
+    NotReduced { path: &'static str, position: usize },
+    /// h_pub or h_sig differs from the holonomy of the private paths
+    HolonomyMismatch { component: &'static str },
+    /// h_pub or h_sig is not in SL(2): det ≠ 1
+    BadDeterminant { component: &'static str },
+    /// desc_m differs from the manifold's descriptor
+    DescriptorMismatch,
+}
+
+impl std::fmt::Display for WitnessViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WitnessViolation::InvalidPathLength { path, len } => {
+                write!(f, "{} has length {}, expected {}", path, len, PATH_LENGTH)
+            }
+            WitnessViolation::IndexOutOfRange { path, position, index } => write!(
+                f,
+                "{}[{}] = {} is not a generator index (< {})",
+                path, position, index, NUM_GENERATOR_INDICES
+            ),
+            WitnessViolation::NotReduced { path, position } => {
+                write!(f, "{} has an inverse pair at positions {} and {}", path, position, position + 1)
+            }
+            WitnessViolation::HolonomyMismatch { component } => {
+                write!(f, "{} does not match the holonomy of the private paths", component)
+            }
+            WitnessViolation::BadDeterminant { component } => write!(f, "{} has det ≠ 1", component),
+            WitnessViolation::DescriptorMismatch => write!(f, "desc_m does not match the manifold descriptor"),
+        }
+    }
+}
+
+impl std::error::Error for WitnessViolation {}
+
+/// Reduce a field element to its low 64 bits: the first 8 bytes of the
+/// little-endian canonical repr. Higher bits are discarded (no modular bias
+/// correction); callers needing uniform ranges should rejection-sample.
+pub fn fr_to_u64_le(f: &Fr) -> u64 {
+    let repr = f.to_repr();
+    let mut low = [0u8; 8];
+    low.copy_from_slice(&repr.as_ref()[..8]);
+    u64::from_le_bytes(low)
+}
+
+/// Witness linking an old public key to a new one during seed rotation
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RotationWitness {
+    /// Public inputs
+    pub h_pub_old: [Fr; 4],
+    pub h_pub_new: [Fr; 4],
+    /// Hol(gamma_old || gamma_new) = h_pub_new · h_pub_old
+    pub h_link: [Fr; 4],
+    pub desc_m: [Fr; 4],
+    pub m_hash: [Fr; 4],
+    /// Private witness
+    pub gamma_old: ReducedPath,
+    pub gamma_new: ReducedPath,
+}
+
+impl RotationWitness {
+    /// Public check that the linking holonomy joins the two announced keys
+    pub fn verify_link(&self) -> bool {
+        let (n, o) = (&self.h_pub_new, &self.h_pub_old);
+        let product = [
+            n[0] * o[0] + n[1] * o[2],
+            n[0] * o[1] + n[1] * o[3],
+            n[2] * o[0] + n[3] * o[2],
+            n[2] * o[1] + n[3] * o[3],
+        ];
+        product == self.h_link
+    }
+
+    /// Express the rotation as a signing-circuit witness: gamma = γ_old, delta = γ_new,
+    /// so the circuit proves H_pub = Hol(γ_old) and H_sig = h_link
+    pub fn to_witness(&self) -> Witness {
+        Witness {
+            h_pub: self.h_pub_old,
+            h_sig: self.h_link,
+            desc_m: self.desc_m,
+            m_hash: self.m_hash,
+            gamma: self.gamma_old.clone(),
+            delta: self.gamma_new.clone(),
+        }
+    }
+}
+
+pub const PATH_LENGTH: usize = 20;
+
+/// Poseidon domain tag of message hashes (`hash_to_4fr`)
+pub const DOMAIN_MHASH: u64 = 2;
+/// Poseidon domain tag of seed derivations (`derive_seed`)
+pub const DOMAIN_SEED: u64 = 3;
+/// Poseidon domain tag of audit-log witness commitments (`commitment`)
+pub const DOMAIN_COMMIT: u64 = 4;
+/// Poseidon domain tag of epoch-bound message hashes (`epoch_message_hash`)
+pub const DOMAIN_EPOCH: u64 = 5;
+
+/// `generate_path_with` reduces indices mod the group's index count and `derive_reduced_path` fixes the
+/// length and removes inverse pairs, so derived paths always validate
+const DERIVED_VALID: &str = "derived paths are reduced, in range and PATH_LENGTH long";
+
+// `bytes_to_frs` and `to_circom_input` assume the BN254 scalar field encoding:
+// a 32-byte little-endian repr. Swapping the field must fail here, not at runtime.
+const_assert_eq!(std::mem::size_of::<<Fr as PrimeField>::Repr>(), 32);
 
 impl Witness {
     /// Generate a complete witness
     pub fn new(message: &[u8], private_seed: &[u8]) -> Self {
-        // 1. Create manifold (genus=5)
-        let manifold = HyperbolicManifold::new();
+        Self::new_prehashed(Self::hash_to_4fr(message), private_seed)
+    }
+
+    /// `new` over another holonomy group. `Witness::new` is `new_in` with the genus-5
+    /// `HyperbolicManifold`, the only group the circuit accepts; witnesses over other
+    /// groups are for off-circuit experiments.
+    pub fn new_in<G: HolonomyGroup>(group: &G, message: &[u8], private_seed: &[u8]) -> Self {
+        Self::new_prehashed_in(group, Self::hash_to_4fr(message), private_seed)
+    }
+
+    /// Witness valid only for `epoch` (e.g. a block height range): the epoch is mixed
+    /// into `m_hash`, and through it into delta. The circuit has no epoch input, so the
+    /// binding rides on the m_hash public input: verifiers recompute it with
+    /// `epoch_message_hash`, and a signature for one epoch fails under any other.
+    pub fn new_with_epoch(message: &[u8], private_seed: &[u8], epoch: u64) -> Self {
+        Self::new_prehashed(Self::epoch_message_hash(message, epoch), private_seed)
+    }
+
+    /// m_hash of an epoch-bound message: H(DOMAIN_EPOCH || epoch || hash_to_4fr(message))
+    pub fn epoch_message_hash(message: &[u8], epoch: u64) -> [Fr; 4] {
+        let mut hasher = PoseidonHasher::<Fr, _, EPOCH_T, EPOCH_RATE>::new(Spec::new());
+        hasher.update(&[Fr::from(DOMAIN_EPOCH), Fr::from(epoch)]);
+        hasher.update(&Self::hash_to_4fr(message));
+        let result = hasher.squeeze();
+        [result[0], result[1], result[2], result[3]]
+    }
 
-        // 2. Derive gamma path from message and private seed
-        let gamma_seed = Self::derive_seed(b"gamma", message, private_seed);
-        let mut gamma = Self::generate_path(&gamma_seed, PATH_LENGTH);
-        Self::ensure_reduced_path(&mut gamma);
+    /// Generate a witness whose `m_hash` uses the given message encoding.
+    /// `MessageEncoding::Legacy` matches `new`; verifiers must use the same encoding.
+    pub fn new_with_encoding(message: &[u8], private_seed: &[u8], encoding: MessageEncoding) -> Self {
+        Self::new_prehashed(encoding.encode(message), private_seed)
+    }
 
-        // 3. Compute public key holonomy: H_pub = Hol(gamma)
+    /// One-shot key: draws a fresh 32-byte seed from `rng` and signs `message` with it.
+    ///
+    /// SECURITY: the returned seed IS the private key. Store it like any other seed
+    /// (it is wiped on drop) or discard it if the key is single-use. `rng` must be a
+    /// CSPRNG seeded from hardware/OS entropy; freshness rests on it alone and cannot
+    /// be proven from the witness.
+    pub fn new_fresh<R: RngCore + CryptoRng>(message: &[u8], rng: &mut R) -> (Self, Zeroizing<[u8; 32]>) {
+        let mut seed = Zeroizing::new([0u8; 32]);
+        rng.fill_bytes(&mut seed[..]);
+        (Self::new(message, &seed[..]), seed)
+    }
+
+    /// Generate a witness for an already-hashed message (sign-prehashed).
+    /// `m_hash` is used directly for the delta derivation and as the public input,
+    /// skipping `hash_to_4fr`. The caller is responsible for domain separation of
+    /// the digest: TopoShield cannot tell which hash function produced it.
+    pub fn new_prehashed(m_hash: [Fr; 4], private_seed: &[u8]) -> Self {
+        Self::new_prehashed_in(&HyperbolicManifold::new(), m_hash, private_seed)
+    }
+
+    /// `new_prehashed` over another holonomy group (see `new_in`)
+    pub fn new_prehashed_in<G: HolonomyGroup>(group: &G, m_hash: [Fr; 4], private_seed: &[u8]) -> Self {
+        // 1. Derive gamma path from the private seed only (stable public key)
+        let gamma = Self::derive_gamma(group, private_seed);
+
+        // 2. Compute public key holonomy: H_pub = Hol(gamma)
         // NOTE: Using CORRECTED order (reversed path) to match mathematical definition
-        let h_pub = Self::compute_holonomy(&gamma, &manifold);
+        let h_pub = Self::compute_holonomy(&gamma, "gamma", group).expect(DERIVED_VALID);
 
-        // 4. Derive delta path from message and public key (RFC 6979-style)
-        let mut pk_bytes = Vec::new();
-        for &elem in &h_pub {
-            pk_bytes.extend_from_slice(elem.to_repr().as_ref());
-        }
-        let delta_seed = Self::derive_seed(b"delta", message, &pk_bytes);
-        let mut delta = Self::generate_path(&delta_seed, PATH_LENGTH);
-        Self::ensure_reduced_path(&mut delta);
+        // 3. Derive delta path from message hash and public key (RFC 6979-style)
+        let m_bytes = Self::frs_to_bytes(&m_hash);
+        let pk_bytes = Self::frs_to_bytes(&h_pub);
+        let delta_seed = Self::derive_seed(b"delta", &m_bytes, &pk_bytes);
+        let mut delta = Self::derive_reduced_path(group, &delta_seed);
+        Self::reduce_boundary(group, &gamma, &mut delta);
+        let delta = ReducedPath::new_in(group, delta).expect(DERIVED_VALID);
+        debug_assert!(!group.is_inverse_pair(gamma[PATH_LENGTH - 1], delta[0]));
 
-        // 5. Compute signature holonomy: H_sig = Hol(gamma || delta)
+        // 4. Compute signature holonomy: H_sig = Hol(gamma || delta)
         // NOTE: Combined path is gamma followed by delta (in natural order)
         let mut combined = Vec::with_capacity(2 * PATH_LENGTH);
         combined.extend_from_slice(&gamma);
         combined.extend_from_slice(&delta);
-        let h_sig = Self::compute_holonomy(&combined, &manifold);
+        let h_sig = Self::compute_holonomy(&combined, "gamma || delta", group).expect(DERIVED_VALID);
 
-        // 6. Compute public inputs
-        let m_hash = Self::hash_to_4fr(message);
-        let desc_m = Self::compute_desc_m(manifold.p_inv);
+        // 5. Compute public inputs
+        let desc_m = group.descriptor();
 
         Self {
             h_pub,
@@ -69,18 +330,83 @@ impl Witness {
         }
     }
 
-    /// Derive a seed using Poseidon: H(label || data1 || data2)
+    /// Public key h_pub = Hol(gamma) of a private seed, without deriving a signature
+    pub fn public_key(private_seed: &[u8]) -> [Fr; 4] {
+        let manifold = HyperbolicManifold::new();
+        Self::compute_holonomy(&Self::derive_gamma(&manifold, private_seed), "gamma", &manifold).expect(DERIVED_VALID)
+    }
+
+    /// True if `seed` derives the key `h_pub` over `manifold`: re-derives gamma and
+    /// compares Hol(gamma). For key recovery and escrow audits.
+    ///
+    /// NOT zero-knowledge: the checker must hold the seed, i.e. the private key itself.
+    /// Proving knowledge of a key without revealing it is what the signing proof does.
+    pub fn verify_seed_binding<G: HolonomyGroup>(h_pub: [Fr; 4], seed: &[u8], manifold: &G) -> bool {
+        let gamma = Self::derive_gamma(manifold, seed);
+        Self::compute_holonomy(&gamma, "gamma", manifold).is_ok_and(|h| h == h_pub)
+    }
+
+    /// Derive the reduced secret path gamma from a private seed
+    fn derive_gamma<G: HolonomyGroup>(group: &G, private_seed: &[u8]) -> ReducedPath {
+        let gamma_seed = Self::derive_seed(b"gamma", private_seed, b"");
+        ReducedPath::new_in(group, Self::derive_reduced_path(group, &gamma_seed)).expect(DERIVED_VALID)
+    }
+
+    /// Build a key-rotation witness linking the key of `old_seed` to that of `new_seed`.
+    /// The linking holonomy Hol(γ_old || γ_new) = Hol(γ_new)·Hol(γ_old) can only be
+    /// produced by someone holding γ_old. `to_witness` maps it onto the signing circuit
+    /// with γ_new in the delta slot.
+    pub fn rotation_proof(old_seed: &[u8], new_seed: &[u8], message: &[u8]) -> RotationWitness {
+        let manifold = HyperbolicManifold::new();
+        let gamma_old = Self::derive_gamma(&manifold, old_seed);
+        let gamma_new = Self::derive_gamma(&manifold, new_seed);
+        let h_pub_old = Self::compute_holonomy(&gamma_old, "gamma_old", &manifold).expect(DERIVED_VALID);
+        let h_pub_new = Self::compute_holonomy(&gamma_new, "gamma_new", &manifold).expect(DERIVED_VALID);
+        let h_link = manifold.holonomy_extend(h_pub_old, &gamma_new);
+
+        RotationWitness {
+            h_pub_old,
+            h_pub_new,
+            h_link,
+            desc_m: manifold.descriptor(),
+            m_hash: Self::hash_to_4fr(message),
+            gamma_old,
+            gamma_new,
+        }
+    }
+
+    /// Concatenate the 32-byte reprs of field elements
+    fn frs_to_bytes(frs: &[Fr]) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(32 * frs.len());
+        for elem in frs {
+            bytes.extend_from_slice(elem.to_repr().as_ref());
+        }
+        bytes
+    }
+
+    /// Derive a seed using Poseidon:
+    /// H(DOMAIN_SEED || len(label) || label || len(data1) || data1 || len(data2) || data2).
+    /// Each field is length-prefixed, so no two (label, data1, data2) splits of the
+    /// same bytes collide.
     fn derive_seed(label: &[u8], data1: &[u8], data2: &[u8]) -> [Fr; 4] {
-        let mut hasher = PoseidonHasher::<Fr, _, 4, 1>::new(Spec::new());
-        let label_fr = Fr::from(label.len() as u64);
-        hasher.update(&[label_fr]);
-        hasher.update(&Self::bytes_to_frs(data1));
-        hasher.update(&Self::bytes_to_frs(data2));
+        let mut hasher = PoseidonHasher::<Fr, _, SEED_T, SEED_RATE>::new(Spec::new());
+        hasher.update(&[Fr::from(DOMAIN_SEED)]);
+        for field in [label, data1, data2] {
+            hasher.update(&[Fr::from(field.len() as u64)]);
+            hasher.update(&Self::bytes_to_frs(field));
+        }
         let result = hasher.squeeze();
         [result[0], result[1], result[2], result[3]]
     }
 
+    /// Hiding commitment to a private seed, domain-separated from the gamma derivation
+    pub(crate) fn seed_commitment(private_seed: &[u8]) -> [Fr; 4] {
+        Self::derive_seed(b"seed-commitment", private_seed, b"")
+    }
+
     /// Convert bytes to field elements (31 bytes per Fr)
+    /// Chunks are written into the low bytes of a 32-byte little-endian repr;
+    /// 31 bytes always stay below the BN254 scalar modulus.
     fn bytes_to_frs(bytes: &[u8]) -> Vec<Fr> {
         let mut frs = Vec::new();
         for chunk in bytes.chunks(31) {
@@ -94,45 +420,72 @@ impl Witness {
         frs
     }
 
-    /// Hash arbitrary bytes to 4 field elements
-    fn hash_to_4fr(bytes: &[u8]) -> [Fr; 4] {
+    /// Hash arbitrary bytes to 4 field elements: H(DOMAIN_MHASH || len || bytes).
+    /// The byte length is absorbed before the chunks: `bytes_to_frs` zero-pads the
+    /// last chunk, so without it "ab" and "ab\0" would hash identically.
+    pub(crate) fn hash_to_4fr(bytes: &[u8]) -> [Fr; 4] {
         let frs = Self::bytes_to_frs(bytes);
-        let mut hasher = PoseidonHasher::<Fr, _, 4, 1>::new(Spec::new());
+        let mut hasher = PoseidonHasher::<Fr, _, MHASH_T, MHASH_RATE>::new(Spec::new());
+        hasher.update(&[Fr::from(DOMAIN_MHASH)]);
+        hasher.update(&[Fr::from(bytes.len() as u64)]);
         hasher.update(&frs);
         let result = hasher.squeeze();
         [result[0], result[1], result[2], result[3]]
     }
 
-    /// Generate a path of given length using PRF from seed
-    fn generate_path(seed: &[Fr; 4], length: usize) -> Vec<u8> {
+    /// Generate a path of given length using PRF from seed, mapping each PRF output
+    /// to an index with `sampler`. The seed is absorbed once; each index clones that
+    /// sponge state and absorbs only the counter. Output is identical to hashing
+    /// H(seed || i) from scratch per index.
+    pub fn generate_path_with<S: PathSampler>(seed: &[Fr; 4], length: usize, sampler: &S) -> Vec<u8> {
+        let mut seeded = PoseidonHasher::<Fr, _, PATH_T, PATH_RATE>::new(Spec::new());
+        seeded.update(seed);
         let mut path = Vec::with_capacity(length);
         for i in 0..length {
-            let mut hasher = PoseidonHasher::<Fr, _, 4, 1>::new(Spec::new());
-            hasher.update(seed);
+            let mut hasher = seeded.clone();
             hasher.update(&[Fr::from(i as u64)]);
             let hash = hasher.squeeze();
-            let index = (u64::from_le_bytes(hash[0].to_repr()[..8].try_into().unwrap_or([0u8; 8])) % 20) as u8;
-            path.push(index);
+            path.push(sampler.sample_index(hash[0]));
         }
         path
     }
 
-    /// Enforce reduced form: remove adjacent inverse pairs (a, a⁻¹) or (b, b⁻¹)
-    fn ensure_reduced_path(path: &mut Vec<u8>) {
+    /// Reduced PATH_LENGTH path over `group` from a PRF seed (see `derive_reduced_path_with`).
+    /// Over `HyperbolicManifold` the draw matches `UniformModSampler`.
+    fn derive_reduced_path<G: HolonomyGroup>(group: &G, seed: &[Fr; 4]) -> Vec<u8> {
+        Self::derive_reduced_path_with(group, seed, |s| {
+            Self::generate_path_with(s, PATH_LENGTH, &GroupSampler(group))
+        })
+    }
+
+    /// Draw a raw path with `draw`, cancel inverse pairs and pad to PATH_LENGTH.
+    /// If cancellation empties the path, padding alone would give a fixed word
+    /// independent of the seed, so the path is re-drawn from
+    /// H("path-retry", seed, attempt) instead.
+    fn derive_reduced_path_with<G: HolonomyGroup>(
+        group: &G,
+        seed: &[Fr; 4],
+        draw: impl Fn(&[Fr; 4]) -> Vec<u8>,
+    ) -> Vec<u8> {
+        let mut attempt_seed = *seed;
+        for attempt in 1u64.. {
+            let mut path = draw(&attempt_seed);
+            Self::cancel_inverse_pairs(group, &mut path);
+            if !path.is_empty() {
+                Self::ensure_reduced_path(group, &mut path);
+                return path;
+            }
+            attempt_seed = Self::derive_seed(b"path-retry", &Self::frs_to_bytes(seed), &attempt.to_le_bytes());
+        }
+        unreachable!("retry counter exhausted")
+    }
+
+    /// Remove adjacent inverse pairs (x, x⁻¹) until none remain
+    fn cancel_inverse_pairs<G: HolonomyGroup>(group: &G, path: &mut Vec<u8>) {
         let mut i = 0;
         while i < path.len().saturating_sub(1) {
-            let a = path[i] as i32;
-            let b = path[i + 1] as i32;
-
-            let is_cancel =
-                // a_i followed by a_i⁻¹
-                (a >= 0 && a <= 4 && b == a + 10) ||
-                // b_i followed by b_i⁻¹
-                (a >= 5 && a <= 9 && b == a + 10) ||
-                // a_i⁻¹ followed by a_i
-                (a >= 10 && a <= 14 && b == a - 10) ||
-                // b_i⁻¹ followed by b_i
-                (a >= 15 && a <= 19 && b == a - 10);
+            // (x, x⁻¹) or (x⁻¹, x) for any generator x
+            let is_cancel = group.is_inverse_pair(path[i], path[i + 1]);
 
             if is_cancel {
                 path.remove(i);
@@ -142,54 +495,239 @@ impl Witness {
                 i += 1;
             }
         }
+    }
+
+    /// Enforce reduced form: remove adjacent inverse pairs (a, a⁻¹) or (b, b⁻¹),
+    /// then pad to PATH_LENGTH with the next index after the last that does not cancel it
+    fn ensure_reduced_path<G: HolonomyGroup>(group: &G, path: &mut Vec<u8>) {
+        Self::cancel_inverse_pairs(group, path);
 
         // Pad to PATH_LENGTH if needed (deterministically)
+        let n = group.num_generators();
         while path.len() < PATH_LENGTH {
             let last = *path.last().unwrap_or(&0);
-            path.push((last + 1) % 20);
+            let next = (1..n)
+                .map(|step| ((last as usize + step) % n) as u8)
+                .find(|&idx| !group.is_inverse_pair(last, idx))
+                .expect("a group has at least two generator indices");
+            path.push(next);
         }
 
         // Truncate if somehow longer (should not happen)
         path.truncate(PATH_LENGTH);
     }
 
+    /// Keep gamma || delta reduced across the junction.
+    /// The circuit only checks gamma and delta separately, so a cancelling pair
+    /// (last of gamma, first of delta) would still prove; we avoid emitting one so
+    /// the combined word is reduced too. If delta[0] cancels gamma's last letter it
+    /// is replaced by the smallest index cancelling neither neighbour; lengths and
+    /// the rest of delta are unchanged.
+    fn reduce_boundary<G: HolonomyGroup>(group: &G, gamma: &[u8], delta: &mut [u8]) {
+        let (Some(&last), Some(&first)) = (gamma.last(), delta.first()) else {
+            return;
+        };
+        if !group.is_inverse_pair(last, first) {
+            return;
+        }
+        let next = delta.get(1).copied();
+        delta[0] = (0..group.num_generators() as u8)
+            .find(|&idx| {
+                !group.is_inverse_pair(last, idx) && next.is_none_or(|n| !group.is_inverse_pair(idx, n))
+            })
+            .expect("at most two indices are excluded and a group has at least four");
+    }
+
     /// Compute exact holonomy for a path using manifold's faithful representation
-    /// CORRECTED: Process path in REVERSE order to match mathematical definition
     /// In mathematics, for path γ = γ₁·γ₂·...·γₙ, Hol(γ) = Hol(γₙ)·...·Hol(γ₂)·Hol(γ₁)
-    fn compute_holonomy(path: &[u8], manifold: &HyperbolicManifold) -> [Fr; 4] {
-        let mut result = [Fr::one(), Fr::zero(), Fr::zero(), Fr::one()]; // Identity
-        // Process path in REVERSE order (from last segment to first)
-        for &idx in path.iter().rev() {
-            let (a, b, c, d) = manifold.get_generator(idx as usize);
-            // Matrix multiplication: result = generator * result
-            // This corresponds to Hol(γₙ)·...·Hol(γ₂)·Hol(γ₁)
-            let new_result = [
-                a * result[0] + b * result[2],
-                a * result[1] + b * result[3],
-                c * result[0] + d * result[2],
-                c * result[1] + d * result[3],
-            ];
-            result = new_result;
-        }
-        result
-    }
-
-    /// Compute manifold descriptor: Poseidon(5, -8, 12345)
-    fn compute_desc_m(p_inv: u64) -> [Fr; 4] {
-        let mut hasher = PoseidonHasher::<Fr, _, 4, 1>::new(Spec::new());
-        hasher.update(&[
-            Fr::from(5u64),        // genus
-            -Fr::from(8u64),       // χ = 2 - 2*5 = -8
-            Fr::from(p_inv),
-        ]);
-        let result = hasher.squeeze();
-        [result[0], result[1], result[2], result[3]]
+    /// (see `HyperbolicManifold::holonomy`, which matches the circuit's PathToHolonomy).
+    /// Indices out of the group's range (e.g. from a deserialized witness) yield
+    /// `IndexOutOfRange` for `name`.
+    fn compute_holonomy<G: HolonomyGroup>(
+        path: &[u8],
+        name: &'static str,
+        group: &G,
+    ) -> Result<[Fr; 4], WitnessViolation> {
+        group
+            .try_holonomy(path)
+            .map_err(|position| WitnessViolation::IndexOutOfRange { path: name, position, index: path[position] })
+    }
+
+    /// h_pub and h_sig as 2×2 matrices, for debugging; `Display` shows trace and det
+    pub fn holonomy_matrices(&self) -> (Sl2Matrix<Fr>, Sl2Matrix<Fr>) {
+        let [a, b, c, d] = self.h_pub;
+        let [e, f, g, h] = self.h_sig;
+        (Sl2Matrix::new(a, b, c, d), Sl2Matrix::new(e, f, g, h))
+    }
+
+    /// gamma as a readable word, e.g. "a1 b3 a2⁻¹ b5 ...". gamma is the private key:
+    /// only print it for test keys.
+    pub fn gamma_word(&self, manifold: &HyperbolicManifold) -> String {
+        manifold.word(&self.gamma)
+    }
+
+    /// delta as a readable word (see `HyperbolicManifold::word`)
+    pub fn delta_word(&self, manifold: &HyperbolicManifold) -> String {
+        manifold.word(&self.delta)
+    }
+
+    /// Trace of the public-key holonomy (conjugacy invariant of gamma)
+    pub fn public_key_trace(&self) -> Fr {
+        self.h_pub[0] + self.h_pub[3]
+    }
+
+    /// Conjugacy-canonical public key: the holonomy of the canonical
+    /// representative of gamma's conjugacy class. Keys whose paths are conjugate
+    /// (e.g. cyclic rotations) share it, so it can be used to deduplicate
+    /// equivalent keys. Unlike `h_pub` it is not bound by the circuit.
+    pub fn canonical_public_key(&self) -> [Fr; 4] {
+        let manifold = HyperbolicManifold::new();
+        manifold.holonomy(&HyperbolicManifold::conjugacy_representative(&self.gamma))
+    }
+
+    /// 32-byte audit-log commitment to the public parts of the witness:
+    /// H(DOMAIN_COMMIT || h_pub || h_sig || desc_m || m_hash || len(delta) || delta),
+    /// the first squeezed element in its little-endian repr. gamma is excluded, so
+    /// the commitment can be logged without the secret.
+    pub fn commitment(&self) -> [u8; 32] {
+        let mut hasher = PoseidonHasher::<Fr, _, COMMIT_T, COMMIT_RATE>::new(Spec::new());
+        hasher.update(&[Fr::from(DOMAIN_COMMIT)]);
+        hasher.update(&self.h_pub);
+        hasher.update(&self.h_sig);
+        hasher.update(&self.desc_m);
+        hasher.update(&self.m_hash);
+        hasher.update(&[Fr::from(self.delta.len() as u64)]);
+        let delta: Vec<Fr> = self.delta.iter().map(|&idx| Fr::from(idx as u64)).collect();
+        hasher.update(&delta);
+        hasher.squeeze()[0].to_repr()
     }
 
-    /// Convert witness to Circom-compatible input format (hex strings for field elements)
+    /// Check every structural invariant of the witness against `manifold`, returning
+    /// the first violation: holonomies, determinants and descriptor. Path length,
+    /// index range and reduced form are guaranteed by `ReducedPath`.
+    /// Never panics on malformed (e.g. deserialized) input.
+    pub fn verify_structural(&self, manifold: &HyperbolicManifold) -> Result<(), WitnessViolation> {
+        match self.verify_structural_verbose(manifold).into_iter().next() {
+            Some(violation) => Err(violation),
+            None => Ok(()),
+        }
+    }
+
+    /// Like `verify_structural`, but collects every violated invariant instead of
+    /// stopping at the first, in the same order. Empty if the witness is valid.
+    /// For raw, not yet validated paths see `ReducedPath::violations`.
+    pub fn verify_structural_verbose(&self, manifold: &HyperbolicManifold) -> Vec<WitnessViolation> {
+        let mut violations = Vec::new();
+        match Self::compute_holonomy(&self.gamma, "gamma", manifold) {
+            Ok(h_pub) => {
+                if h_pub != self.h_pub {
+                    violations.push(WitnessViolation::HolonomyMismatch { component: "h_pub" });
+                }
+                if !manifold.verify_signature_composition(h_pub, &self.delta, self.h_sig) {
+                    violations.push(WitnessViolation::HolonomyMismatch { component: "h_sig" });
+                }
+            }
+            Err(violation) => violations.push(violation),
+        }
+        for (name, h) in [("h_pub", &self.h_pub), ("h_sig", &self.h_sig)] {
+            if h[0] * h[3] - h[1] * h[2] != Fr::one() {
+                violations.push(WitnessViolation::BadDeterminant { component: name });
+            }
+        }
+        if self.desc_m != manifold.descriptor() {
+            violations.push(WitnessViolation::DescriptorMismatch);
+        }
+        violations
+    }
+
+    /// Pack gamma and delta for storage: 5 bits per generator index (20 < 32).
+    /// Layout: [len(gamma), len(delta)] followed by the indices of gamma || delta,
+    /// bit-packed LSB-first. A 20+20 witness packs into 2 + 25 = 27 bytes instead of 40.
+    pub fn pack_paths(&self) -> Vec<u8> {
+        let total = self.gamma.len() + self.delta.len();
+        let mut out = Vec::with_capacity(2 + (total * 5).div_ceil(8));
+        out.push(self.gamma.len() as u8);
+        out.push(self.delta.len() as u8);
+
+        let mut acc: u32 = 0;
+        let mut bits = 0;
+        for &idx in self.gamma.iter().chain(self.delta.iter()) {
+            acc |= ((idx & 0x1f) as u32) << bits;
+            bits += 5;
+            while bits >= 8 {
+                out.push(acc as u8);
+                acc >>= 8;
+                bits -= 8;
+            }
+        }
+        if bits > 0 {
+            out.push(acc as u8);
+        }
+        out
+    }
+
+    /// Inverse of `pack_paths`: returns (gamma, delta), or None if the buffer is
+    /// truncated, has trailing bytes, or encodes an index outside 0–19
+    pub fn unpack_paths(bytes: &[u8]) -> Option<(Vec<u8>, Vec<u8>)> {
+        let (&gamma_len, rest) = bytes.split_first()?;
+        let (&delta_len, packed) = rest.split_first()?;
+        let total = gamma_len as usize + delta_len as usize;
+        if packed.len() != (total * 5).div_ceil(8) {
+            return None;
+        }
+
+        let mut indices = Vec::with_capacity(total);
+        let mut packed = packed.iter();
+        let mut acc: u32 = 0;
+        let mut bits = 0;
+        while indices.len() < total {
+            while bits < 5 {
+                acc |= (*packed.next()? as u32) << bits;
+                bits += 8;
+            }
+            let idx = (acc & 0x1f) as u8;
+            if idx >= 20 {
+                return None;
+            }
+            indices.push(idx);
+            acc >>= 5;
+            bits -= 5;
+        }
+
+        let delta = indices.split_off(gamma_len as usize);
+        Some((indices, delta))
+    }
+
+    /// Fingerprint of the Poseidon parameter set (round constants + MDS matrix).
+    /// The sponge output on a fixed probe input depends on every constant, so any
+    /// change to `Spec::new()` (e.g. after a dependency bump) changes this id.
+    pub fn poseidon_spec_id() -> Fr {
+        let mut hasher = PoseidonHasher::<Fr, _, SPEC_ID_T, SPEC_ID_RATE>::new(Spec::new());
+        let probe: Vec<Fr> = (0..8u64).map(Fr::from).collect();
+        hasher.update(&probe);
+        hasher.squeeze()[0]
+    }
+
+    /// Convert witness to Circom-compatible input format (hex strings for field elements).
+    /// `num_generators` is the bound of the circuit's range check on every path index
+    /// (its lookup table maps unknown indices to the identity). Indices are checked
+    /// against it here too, so a bad path is a typed error rather than a failed proof.
+    /// Output is deterministic: the `BTreeMap` serializes in key order and values are
+    /// only arrays, strings and integers, so `serde_json::to_string` of identical
+    /// witnesses is byte-identical (suitable for content-addressed input.json caches).
     /// NOTE: Since holonomy computation now uses reverse path order,
     /// Circom circuit must be updated to process path in natural order
-    pub fn to_circom_input(&self) -> BTreeMap<String, serde_json::Value> {
+    pub fn to_circom_input(&self) -> Result<BTreeMap<String, serde_json::Value>, WitnessViolation> {
+        for (path, indices) in [("gamma", &self.gamma), ("delta", &self.delta)] {
+            if let Some((position, &index)) = indices
+                .iter()
+                .enumerate()
+                .find(|(_, &idx)| idx as usize >= NUM_GENERATOR_INDICES)
+            {
+                return Err(WitnessViolation::IndexOutOfRange { path, position, index });
+            }
+        }
+
         let fr_to_hex = |f: &Fr| format!("0x{}", hex::encode(f.to_repr()));
         let mut input = BTreeMap::new();
         input.insert("H_pub".to_string(), serde_json::json!(self.h_pub.iter().map(fr_to_hex).collect::<Vec<_>>()));
@@ -197,15 +735,17 @@ impl Witness {
         input.insert("desc_M".to_string(), serde_json::json!(self.desc_m.iter().map(fr_to_hex).collect::<Vec<_>>()));
         input.insert("m_hash".to_string(), serde_json::json!(self.m_hash.iter().map(fr_to_hex).collect::<Vec<_>>()));
         // IMPORTANT: Pass paths in NATURAL order (Circom circuit must process in reverse)
-        input.insert("gamma".to_string(), serde_json::json!(self.gamma));
-        input.insert("delta".to_string(), serde_json::json!(self.delta));
-        input
+        input.insert("gamma".to_string(), serde_json::json!(*self.gamma));
+        input.insert("delta".to_string(), serde_json::json!(*self.delta));
+        input.insert("num_generators".to_string(), serde_json::json!(NUM_GENERATOR_INDICES));
+        Ok(input)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sampler::UniformModSampler;
 
     #[test]
     fn test_witness_generation_consistency() {
@@ -240,7 +780,8 @@ mod tests {
     #[test]
     fn test_circom_input_format() {
         let w = Witness::new(b"Circom Test", b"circom_seed");
-        let input = w.to_circom_input();
+        let input = w.to_circom_input().unwrap();
+        assert_eq!(input["num_generators"], serde_json::json!(20));
         assert!(input.contains_key("gamma"));
         assert!(input.contains_key("delta"));
         assert!(input.contains_key("H_pub"));
@@ -249,6 +790,23 @@ mod tests {
         assert!(input.contains_key("m_hash"));
     }
 
+    #[test]
+    fn test_circom_input_serialization_is_stable() {
+        let serialize = || {
+            let input = Witness::new(b"Circom Test", b"circom_seed").to_circom_input().unwrap();
+            serde_json::to_string(&input).unwrap()
+        };
+        let (first, second) = (serialize(), serialize());
+        assert_eq!(first.as_bytes(), second.as_bytes());
+
+        // Keys appear in sorted order regardless of insertion order
+        let positions: Vec<usize> = ["H_pub", "H_sig", "delta", "desc_M", "gamma", "m_hash", "num_generators"]
+            .iter()
+            .map(|key| first.find(&format!("\"{}\":", key)).expect("key present"))
+            .collect();
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]), "{}", first);
+    }
+
     #[test]
     fn test_holonomy_reversal() {
         // Test that reversing path order gives different holonomy
@@ -258,10 +816,505 @@ mod tests {
         let path1 = vec![0, 1];
         let path2 = vec![1, 0];
         
-        let hol1 = Witness::compute_holonomy(&path1, &manifold);
-        let hol2 = Witness::compute_holonomy(&path2, &manifold);
+        let hol1 = Witness::compute_holonomy(&path1, "path1", &manifold).unwrap();
+        let hol2 = Witness::compute_holonomy(&path2, "path2", &manifold).unwrap();
         
         // These should be different because matrix multiplication is not commutative
         assert_ne!(hol1, hol2, "Reversed paths should produce different holonomies");
     }
+
+    #[test]
+    fn test_pack_paths_roundtrip() {
+        let base = Witness::new(b"Pack Test", b"pack_seed");
+        let manifold = HyperbolicManifold::new();
+        for i in 0u64..32 {
+            let gamma_seed = Witness::derive_seed(b"pack_gamma", &i.to_le_bytes(), b"");
+            let delta_seed = Witness::derive_seed(b"pack_delta", &i.to_le_bytes(), b"");
+            let mut w = base.clone();
+            let mut gamma = Witness::generate_path_with(&gamma_seed, PATH_LENGTH, &UniformModSampler);
+            Witness::ensure_reduced_path(&manifold, &mut gamma);
+            w.gamma = ReducedPath::new(gamma).unwrap();
+            let mut delta = Witness::generate_path_with(&delta_seed, PATH_LENGTH, &UniformModSampler);
+            Witness::ensure_reduced_path(&manifold, &mut delta);
+            w.delta = ReducedPath::new(delta).unwrap();
+
+            let packed = w.pack_paths();
+            assert_eq!(packed.len(), 2 + (2 * PATH_LENGTH * 5).div_ceil(8));
+            let (gamma, delta) = Witness::unpack_paths(&packed).expect("valid packing");
+            assert_eq!(gamma, w.gamma.to_vec());
+            assert_eq!(delta, w.delta.to_vec());
+        }
+    }
+
+    #[test]
+    fn test_unpack_paths_rejects_malformed() {
+        let w = Witness::new(b"Pack Test", b"pack_seed");
+        let packed = w.pack_paths();
+        assert!(Witness::unpack_paths(&packed[..packed.len() - 1]).is_none());
+        assert!(Witness::unpack_paths(&[]).is_none());
+        // Single index 31 (≥ 20) must be rejected
+        assert!(Witness::unpack_paths(&[1, 0, 0x1f]).is_none());
+    }
+
+    #[test]
+    fn test_poseidon_spec_id_stable() {
+        let id1 = Witness::poseidon_spec_id();
+        let id2 = Witness::poseidon_spec_id();
+        assert_eq!(id1, id2);
+        assert_ne!(id1, Fr::zero());
+    }
+
+    #[test]
+    fn test_public_key_trace_matches_manifold() {
+        let w = Witness::new(b"Trace Test", b"trace_seed");
+        let manifold = HyperbolicManifold::new();
+        assert_eq!(w.public_key_trace(), manifold.holonomy_trace(&w.gamma));
+    }
+
+    #[test]
+    fn test_generate_path_matches_unbatched() {
+        let seed = Witness::derive_seed(b"gamma", b"msg", b"seed");
+        let path = Witness::generate_path_with(&seed, PATH_LENGTH, &UniformModSampler);
+        for (i, &idx) in path.iter().enumerate() {
+            let mut hasher = PoseidonHasher::<Fr, _, PATH_T, PATH_RATE>::new(Spec::new());
+            hasher.update(&seed);
+            hasher.update(&[Fr::from(i as u64)]);
+            let hash = hasher.squeeze();
+            let expected = (fr_to_u64_le(&hash[0]) % 20) as u8;
+            assert_eq!(idx, expected);
+        }
+    }
+
+    #[test]
+    fn test_signature_composes_with_public_key() {
+        let w = Witness::new(b"Composition Test", b"composition_seed");
+        let manifold = HyperbolicManifold::new();
+        assert!(manifold.verify_signature_composition(w.h_pub, &w.delta, w.h_sig));
+    }
+
+    #[test]
+    fn test_new_prehashed_matches_preimage() {
+        let message = b"Prehashed Test";
+        let seed = b"prehash_seed";
+        let from_message = Witness::new(message, seed);
+        let from_hash = Witness::new_prehashed(Witness::hash_to_4fr(message), seed);
+        assert_eq!(from_message.gamma, from_hash.gamma);
+        assert_eq!(from_message.delta, from_hash.delta);
+        assert_eq!(from_message.h_pub, from_hash.h_pub);
+        assert_eq!(from_message.h_sig, from_hash.h_sig);
+        assert_eq!(from_message.m_hash, from_hash.m_hash);
+    }
+
+    #[test]
+    fn test_rotation_witness_links_keys() {
+        let rotation = Witness::rotation_proof(b"old_seed", b"new_seed", b"rotate");
+        let old = Witness::new(b"any message", b"old_seed");
+        let new = Witness::new(b"any message", b"new_seed");
+        assert_eq!(rotation.h_pub_old, old.h_pub);
+        assert_eq!(rotation.h_pub_new, new.h_pub);
+        assert!(rotation.verify_link());
+
+        let manifold = HyperbolicManifold::new();
+        let w = rotation.to_witness();
+        assert!(manifold.verify_signature_composition(w.h_pub, &w.delta, w.h_sig));
+
+        let mut tampered = rotation.clone();
+        tampered.h_pub_new[0] += Fr::one();
+        assert!(!tampered.verify_link());
+    }
+
+    #[test]
+    fn test_fr_to_u64_le() {
+        assert_eq!(fr_to_u64_le(&Fr::zero()), 0);
+        assert_eq!(fr_to_u64_le(&Fr::from(42u64)), 42);
+        assert_eq!(fr_to_u64_le(&Fr::from(u64::MAX)), u64::MAX);
+        // 2^64 has all-zero low limb
+        let two_pow_64 = Fr::from(u64::MAX) + Fr::one();
+        assert_eq!(fr_to_u64_le(&two_pow_64), 0);
+        assert_eq!(fr_to_u64_le(&(two_pow_64 + Fr::from(7u64))), 7);
+    }
+
+    #[test]
+    fn test_verify_structural() {
+        let manifold = HyperbolicManifold::new();
+        let w = Witness::new(b"Structural Test", b"structural_seed");
+        assert_eq!(w.verify_structural(&manifold), Ok(()));
+
+        let mut bad_pub = w.clone();
+        bad_pub.h_pub[1] += Fr::one();
+        assert_eq!(
+            bad_pub.verify_structural(&manifold),
+            Err(WitnessViolation::HolonomyMismatch { component: "h_pub" })
+        );
+
+        let mut tampered = w.clone();
+        tampered.h_sig[0] += Fr::one();
+        assert_eq!(
+            tampered.verify_structural(&manifold),
+            Err(WitnessViolation::HolonomyMismatch { component: "h_sig" })
+        );
+    }
+
+    #[test]
+    fn test_verify_structural_verbose_reports_all() {
+        let manifold = HyperbolicManifold::new();
+        let w = Witness::new(b"Verbose", b"verbose_seed");
+        assert!(w.verify_structural_verbose(&manifold).is_empty());
+
+        // det(h_pub) becomes 1 + h_pub[3]; h_sig and desc_m are tampered too
+        let mut broken = w.clone();
+        broken.h_pub[0] += Fr::one();
+        broken.h_sig[1] += Fr::one();
+        broken.desc_m[2] += Fr::one();
+        let violations = broken.verify_structural_verbose(&manifold);
+        assert_eq!(
+            violations,
+            vec![
+                WitnessViolation::HolonomyMismatch { component: "h_pub" },
+                WitnessViolation::HolonomyMismatch { component: "h_sig" },
+                WitnessViolation::BadDeterminant { component: "h_pub" },
+                WitnessViolation::BadDeterminant { component: "h_sig" },
+                WitnessViolation::DescriptorMismatch,
+            ]
+        );
+        assert_eq!(broken.verify_structural(&manifold), Err(violations[0].clone()));
+    }
+
+    #[test]
+    fn test_reduced_path_violations_reports_all() {
+        let mut raw = Witness::new(b"Verbose", b"verbose_seed").gamma.into_inner();
+        raw[2] = 21;
+        raw[9] = 30;
+        raw[15] = HyperbolicManifold::inverse_index(raw[14]);
+        raw.push(0);
+        let violations = ReducedPath::violations("gamma", &raw);
+        assert_eq!(violations[0], WitnessViolation::InvalidPathLength { path: "gamma", len: PATH_LENGTH + 1 });
+        assert!(violations.contains(&WitnessViolation::IndexOutOfRange { path: "gamma", position: 2, index: 21 }));
+        assert!(violations.contains(&WitnessViolation::IndexOutOfRange { path: "gamma", position: 9, index: 30 }));
+        assert!(violations.contains(&WitnessViolation::NotReduced { path: "gamma", position: 14 }));
+        assert_eq!(ReducedPath::new(raw), Err(violations[0].clone()));
+    }
+
+    #[test]
+    fn test_boundary_reduction() {
+        // gamma ends in a1 (0), delta starts with a1⁻¹ (10): cancels at the junction
+        let manifold = HyperbolicManifold::new();
+        let gamma = vec![3u8, 7, 0];
+        let mut delta = vec![10u8, 1, 5];
+        Witness::reduce_boundary(&manifold, &gamma, &mut delta);
+        assert_eq!(delta, vec![0, 1, 5]);
+        assert!(HyperbolicManifold::is_combined_reduced(&gamma, &delta));
+
+        // Non-cancelling junction is left untouched
+        let mut clean = vec![4u8, 1, 5];
+        Witness::reduce_boundary(&manifold, &gamma, &mut clean);
+        assert_eq!(clean, vec![4, 1, 5]);
+
+        for i in 0u8..16 {
+            let w = Witness::new(&[i], b"boundary_seed");
+            assert!(HyperbolicManifold::is_combined_reduced(&w.gamma, &w.delta));
+            assert_eq!(w.verify_structural(&HyperbolicManifold::new()), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_canonical_public_key_of_rotated_gamma() {
+        let witness = Witness::new(b"Canonical key", b"canonical_seed");
+        let mut rotated = witness.gamma.to_vec();
+        rotated.rotate_left(3);
+        let m = HyperbolicManifold::new();
+        let rotated_h_pub = m.holonomy(&rotated);
+
+        // Rotated paths give different (conjugate) keys with the same trace...
+        assert_ne!(witness.h_pub, rotated_h_pub);
+        assert_eq!(witness.public_key_trace(), rotated_h_pub[0] + rotated_h_pub[3]);
+        // ...but the same canonical key
+        assert_eq!(
+            witness.canonical_public_key(),
+            m.holonomy(&HyperbolicManifold::conjugacy_representative(&rotated))
+        );
+    }
+
+    #[test]
+    fn test_new_fresh_draws_distinct_keys() {
+        let mut rng = rand::rngs::OsRng;
+        let (first, first_seed) = Witness::new_fresh(b"Fresh key", &mut rng);
+        let (second, second_seed) = Witness::new_fresh(b"Fresh key", &mut rng);
+        assert_ne!(*first_seed, *second_seed);
+        assert_ne!(first.h_pub, second.h_pub);
+        // The returned seed reproduces the witness
+        assert_eq!(Witness::new(b"Fresh key", &first_seed[..]).h_sig, first.h_sig);
+    }
+
+    #[test]
+    fn test_derive_seed_fields_are_delimited() {
+        assert_ne!(Witness::derive_seed(b"l", b"ab", b"c"), Witness::derive_seed(b"l", b"a", b"bc"));
+        assert_ne!(Witness::derive_seed(b"l", b"ab", b""), Witness::derive_seed(b"l", b"ab\0", b""));
+        // Labels of equal length are distinct too
+        assert_ne!(Witness::derive_seed(b"gamma", b"x", b""), Witness::derive_seed(b"delta", b"x", b""));
+    }
+
+    #[test]
+    fn test_commitment_covers_public_fields_only() {
+        let w = Witness::new(b"Audit log", b"audit_seed");
+        let commitment = w.commitment();
+        assert_eq!(commitment, Witness::new(b"Audit log", b"audit_seed").commitment());
+
+        let mut tampered = Vec::new();
+        for field in 0..4 {
+            let mut t = w.clone();
+            match field {
+                0 => t.h_pub[0] += Fr::one(),
+                1 => t.h_sig[1] += Fr::one(),
+                2 => t.desc_m[2] += Fr::one(),
+                _ => t.m_hash[3] += Fr::one(),
+            }
+            tampered.push(t);
+        }
+        let mut other_delta = w.clone();
+        other_delta.delta = Witness::new(b"Other message", b"audit_seed").delta;
+        tampered.push(other_delta);
+        for t in &tampered {
+            assert_ne!(t.commitment(), commitment);
+        }
+
+        // gamma is not committed
+        let mut other_gamma = w.clone();
+        other_gamma.gamma = Witness::new(b"Audit log", b"other_seed").gamma;
+        assert_ne!(other_gamma.gamma, w.gamma);
+        assert_eq!(other_gamma.commitment(), commitment);
+    }
+
+    #[test]
+    fn test_fully_cancelling_path_is_redrawn() {
+        // First draw cancels to the empty word; later draws use the real PRF
+        let draw = |seed: &[Fr; 4], cancelling: &[Fr; 4]| {
+            if seed == cancelling {
+                [0u8, 10].repeat(PATH_LENGTH / 2)
+            } else {
+                Witness::generate_path_with(seed, PATH_LENGTH, &UniformModSampler)
+            }
+        };
+        let seed_a = Witness::derive_seed(b"cancel", b"a", b"");
+        let seed_b = Witness::derive_seed(b"cancel", b"b", b"");
+        let manifold = HyperbolicManifold::new();
+        let path_a = Witness::derive_reduced_path_with(&manifold, &seed_a, |s| draw(s, &seed_a));
+        let path_b = Witness::derive_reduced_path_with(&manifold, &seed_b, |s| draw(s, &seed_b));
+
+        // Not the seed-independent padding of an empty word
+        let mut padded_empty = Vec::new();
+        Witness::ensure_reduced_path(&manifold, &mut padded_empty);
+        for path in [&path_a, &path_b] {
+            assert_ne!(path, &padded_empty);
+            assert!(ReducedPath::new(path.clone()).is_ok());
+        }
+        assert_ne!(path_a, path_b, "re-drawn path must still depend on the seed");
+    }
+
+    #[test]
+    fn test_hash_to_4fr_binds_length() {
+        assert_ne!(Witness::hash_to_4fr(b"ab"), Witness::hash_to_4fr(b"ab\0"));
+        assert_ne!(Witness::hash_to_4fr(b""), Witness::hash_to_4fr(&[0u8; 31]));
+        assert_ne!(
+            Witness::new(b"ab", b"length_seed").h_sig,
+            Witness::new(b"ab\0", b"length_seed").h_sig
+        );
+    }
+
+    #[test]
+    fn test_reduced_path_rejects_invalid_paths() {
+        let valid = Witness::new(b"Reduced path", b"reduced_seed").gamma.into_inner();
+        assert_eq!(ReducedPath::new(valid.clone()).map(ReducedPath::into_inner), Ok(valid.clone()));
+
+        // Out-of-range index: the circuit's lookup table would map it to the identity
+        let mut bad_index = valid.clone();
+        bad_index[7] = 20;
+        assert_eq!(
+            ReducedPath::new(bad_index),
+            Err(WitnessViolation::IndexOutOfRange { path: "path", position: 7, index: 20 })
+        );
+
+        // Unreduced input is rejected, not silently reduced
+        let mut unreduced = valid.clone();
+        unreduced[5] = HyperbolicManifold::inverse_index(unreduced[4]);
+        assert_eq!(
+            ReducedPath::new(unreduced),
+            Err(WitnessViolation::NotReduced { path: "path", position: 4 })
+        );
+
+        assert_eq!(
+            ReducedPath::new(valid[..PATH_LENGTH - 1].to_vec()),
+            Err(WitnessViolation::InvalidPathLength { path: "path", len: PATH_LENGTH - 1 })
+        );
+    }
+
+    #[test]
+    fn test_deserialization_validates_paths() {
+        let w = Witness::new(b"Reduced path", b"reduced_seed");
+        let mut json = serde_json::to_value(&w).unwrap();
+        assert!(serde_json::from_value::<Witness>(json.clone()).is_ok());
+
+        json["delta"][3] = serde_json::json!(25);
+        let err = serde_json::from_value::<Witness>(json).unwrap_err();
+        assert!(err.to_string().contains("not a generator index"), "{}", err);
+    }
+
+    #[test]
+    fn test_compute_holonomy_rejects_out_of_range_index() {
+        let manifold = HyperbolicManifold::new();
+        assert_eq!(
+            Witness::compute_holonomy(&[0, 1, 25], "gamma", &manifold),
+            Err(WitnessViolation::IndexOutOfRange { path: "gamma", position: 2, index: 25 })
+        );
+    }
+
+    #[test]
+    fn test_domain_separated_hashes() {
+        // Without tags these preimages absorb identical field elements:
+        // derive_seed("", x, "") absorbs [len("") = 0, x, 0] and hash_to_4fr absorbs
+        // the 31-byte chunks [0, x, 0] of zeros || x || zeros.
+        let x = [7u8; 31];
+        let message = [[0u8; 31], x, [0u8; 31]].concat();
+        assert_ne!(Witness::hash_to_4fr(&message), Witness::derive_seed(b"", &x, b""));
+
+        // desc_m preimage (genus, χ, p_inv) = (5, 8, 12345) vs. a message encoding it
+        let mut encoded = Vec::new();
+        for value in [5u64, 8, 12345] {
+            let mut chunk = [0u8; 31];
+            chunk[..8].copy_from_slice(&value.to_le_bytes());
+            encoded.extend_from_slice(&chunk);
+        }
+        let desc_m = HyperbolicManifold::descriptor_from(5, 8, 12345, HyperbolicManifold::new().generator_traces());
+        assert_ne!(Witness::hash_to_4fr(&encoded), desc_m);
+        assert_ne!(Witness::derive_seed(b"", &encoded, b""), desc_m);
+    }
+
+    #[test]
+    fn test_holonomy_matrices_display() {
+        let w = Witness::new(b"Matrix display", b"display_seed");
+        let (h_pub, h_sig) = w.holonomy_matrices();
+        assert_eq!([h_pub.a, h_pub.b, h_pub.c, h_pub.d], w.h_pub);
+        assert_eq!([h_sig.a, h_sig.b, h_sig.c, h_sig.d], w.h_sig);
+
+        let one = format!("{:?}", Fr::one());
+        for matrix in [h_pub, h_sig] {
+            let shown = matrix.to_string();
+            assert!(shown.starts_with("[["));
+            assert!(shown.ends_with(&format!("det = {})", one)), "{}", shown);
+        }
+    }
+
+    /// Free group on two parabolic generators (Sanov): indices a, b, a⁻¹, b⁻¹
+    struct ToyGroup;
+
+    impl HolonomyGroup for ToyGroup {
+        fn num_generators(&self) -> usize {
+            4
+        }
+
+        fn get_generator(&self, idx: usize) -> Option<[Fr; 4]> {
+            let two = Fr::from(2);
+            match idx {
+                0 => Some([Fr::one(), two, Fr::zero(), Fr::one()]),
+                1 => Some([Fr::one(), Fr::zero(), two, Fr::one()]),
+                2 => Some([Fr::one(), -two, Fr::zero(), Fr::one()]),
+                3 => Some([Fr::one(), Fr::zero(), -two, Fr::one()]),
+                _ => None,
+            }
+        }
+
+        fn inverse_index(&self, idx: u8) -> u8 {
+            (idx + 2) % 4
+        }
+
+        fn descriptor(&self) -> [Fr; 4] {
+            [Fr::from(2), Fr::zero(), Fr::zero(), Fr::zero()]
+        }
+    }
+
+    #[test]
+    fn test_witness_over_toy_group() {
+        let toy = ToyGroup;
+        let w = Witness::new_in(&toy, b"toy message", b"toy_seed");
+        for path in [&w.gamma, &w.delta] {
+            assert_eq!(ReducedPath::violations_in(&toy, "path", path), vec![]);
+        }
+        assert!(!toy.is_inverse_pair(w.gamma[PATH_LENGTH - 1], w.delta[0]));
+        assert_eq!(Ok(w.h_pub), toy.try_holonomy(&w.gamma));
+        assert_eq!(Ok(w.h_sig), toy.try_holonomy(&[&w.gamma[..], &w.delta[..]].concat()));
+        assert_eq!(w.desc_m, toy.descriptor());
+        assert_eq!(w.m_hash, Witness::hash_to_4fr(b"toy message"));
+        assert!(ReducedPath::new_in(&toy, vec![0; PATH_LENGTH - 1].into_iter().chain([4]).collect()).is_err());
+
+        // `new` is `new_in` over the default manifold
+        let default = Witness::new(b"toy message", b"toy_seed");
+        let explicit = Witness::new_in(&HyperbolicManifold::new(), b"toy message", b"toy_seed");
+        assert_eq!(
+            (explicit.h_pub, explicit.h_sig, explicit.desc_m, &explicit.gamma, &explicit.delta),
+            (default.h_pub, default.h_sig, default.desc_m, &default.gamma, &default.delta)
+        );
+    }
+
+    #[test]
+    fn test_verify_seed_binding() {
+        let manifold = HyperbolicManifold::new();
+        let h_pub = Witness::new(b"escrow", b"escrow_seed").h_pub;
+        assert!(Witness::verify_seed_binding(h_pub, b"escrow_seed", &manifold));
+        assert!(!Witness::verify_seed_binding(h_pub, b"other_seed", &manifold));
+        assert!(!Witness::verify_seed_binding(h_pub, b"escrow_seed", &ToyGroup));
+    }
+
+    #[test]
+    fn test_path_words_roundtrip() {
+        let manifold = HyperbolicManifold::new();
+        let w = Witness::new(b"words", b"word_seed");
+        let gamma = w.gamma_word(&manifold);
+        assert_eq!(gamma.split(' ').count(), PATH_LENGTH);
+        assert_eq!(HyperbolicManifold::parse_word(&gamma), Some(w.gamma.to_vec()));
+        assert_eq!(HyperbolicManifold::parse_word(&w.delta_word(&manifold)), Some(w.delta.to_vec()));
+    }
+
+    /// Average fraction of delta positions and of h_sig entries that change when
+    /// one message bit flips, over `samples` single-bit flips of a 32-byte message
+    fn diffusion(seed: &[u8], samples: usize) -> (f64, f64) {
+        let base_message = [0x5au8; 32];
+        let base = Witness::new(&base_message, seed);
+        let (mut path_changed, mut hol_changed) = (0usize, 0usize);
+        for i in 0..samples {
+            let mut message = base_message;
+            let bit = i % (8 * message.len());
+            message[bit / 8] ^= 1 << (bit % 8);
+            let flipped = Witness::new(&message, seed);
+            path_changed += base.delta.iter().zip(flipped.delta.iter()).filter(|(a, b)| a != b).count();
+            hol_changed += base.h_sig.iter().zip(flipped.h_sig.iter()).filter(|(a, b)| a != b).count();
+        }
+        (
+            path_changed as f64 / (samples * PATH_LENGTH) as f64,
+            hol_changed as f64 / (samples * 4) as f64,
+        )
+    }
+
+    #[test]
+    fn test_message_diffusion() {
+        // A fresh uniform index differs from a fixed one with probability 19/20, so an
+        // ideal PRF changes ~95% of delta positions (not 50%: indices are not bits)
+        let (path_fraction, hol_fraction) = diffusion(b"diffusion_seed", 64);
+        assert!(path_fraction > 0.85, "delta diffusion {:.3}", path_fraction);
+        assert!(hol_fraction > 0.99, "h_sig diffusion {:.3}", hol_fraction);
+    }
+
+    #[test]
+    fn test_epoch_binds_signature() {
+        let epoch_7 = Witness::new_with_epoch(b"epoch message", b"epoch_seed", 7);
+        let epoch_8 = Witness::new_with_epoch(b"epoch message", b"epoch_seed", 8);
+        let plain = Witness::new(b"epoch message", b"epoch_seed");
+        assert_eq!(epoch_7.h_pub, epoch_8.h_pub, "the key does not depend on the epoch");
+        for other in [&epoch_8, &plain] {
+            assert_ne!(epoch_7.m_hash, other.m_hash);
+            assert_ne!(epoch_7.delta, other.delta);
+            assert_ne!(epoch_7.h_sig, other.h_sig);
+        }
+        assert_eq!(epoch_7.m_hash, Witness::epoch_message_hash(b"epoch message", 7));
+        assert_eq!(epoch_7.verify_structural(&HyperbolicManifold::new()), Ok(()));
+    }
 }
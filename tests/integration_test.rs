@@ -1,7 +1,9 @@
 // tests/integration_test.rs
 // End-to-end integration test for Enhanced TopoShield ZKP system
 // Verifies full lifecycle with structural validation (reduced paths, enhanced desc_M)
-use toposhield::{manifold::HyperbolicManifold, witness::Witness, prover::TopoShieldProver};
+use toposhield::{
+    error::ToposhieldError, manifold::HyperbolicManifold, prover::TopoShieldProver, witness::Witness,
+};
 
 #[test]
 fn test_toposhield_full_lifecycle() -> Result<(), Box<dyn std::error::Error>> {
@@ -53,8 +55,15 @@ fn test_toposhield_full_lifecycle() -> Result<(), Box<dyn std::error::Error>> {
 
     // 7. Generate ZK proof
     let proof = prover.prove(witness.clone())?;
-    assert!(!proof.is_empty(), "Proof must be non-empty");
-    assert!(proof.len() > 2000 && proof.len() < 3000, "Proof size should be ~2.3 KB");
+    assert!(!proof.bytes.is_empty(), "Proof must be non-empty");
+    let expected = prover.expected_proof_size();
+    assert!(
+        expected.contains(&proof.bytes.len()),
+        "Proof size {} outside the range {:?} derived from the circuit",
+        proof.bytes.len(),
+        expected
+    );
+    assert_eq!(proof.poseidon_spec_id, prover.poseidon_spec_id());
 
     // 8. Verify proof
     let is_valid = prover.verify(
@@ -66,9 +75,8 @@ fn test_toposhield_full_lifecycle() -> Result<(), Box<dyn std::error::Error>> {
     )?;
     assert!(is_valid, "Proof must verify successfully");
 
-    // 9. Tamper test: modify public key → proof must fail
-    let mut tampered_h_pub = witness.h_pub;
-    tampered_h_pub[0] += halo2_proofs::halo2curves::bn256::Fr::one();
+    // 9. Tamper test: another signer's public key → proof must fail
+    let tampered_h_pub = Witness::public_key(b"another signer");
     let is_invalid = prover.verify(
         &proof,
         tampered_h_pub,
@@ -78,18 +86,1163 @@ fn test_toposhield_full_lifecycle() -> Result<(), Box<dyn std::error::Error>> {
     )?;
     assert!(!is_invalid, "Tampered proof must fail verification");
 
-    // 10. Tamper test: modify desc_M → proof must fail
+    // 10. Tamper test: modify desc_M → rejected as an unsupported descriptor
     let mut tampered_desc_m = witness.desc_m;
     tampered_desc_m[0] += halo2_proofs::halo2curves::bn256::Fr::one();
-    let is_invalid_desc = prover.verify(
+    let invalid_desc = prover.verify(
         &proof,
         witness.h_pub,
         witness.h_sig,
         tampered_desc_m,
         witness.m_hash,
+    );
+    assert!(
+        matches!(invalid_desc, Err(ToposhieldError::UnsupportedDescriptor { .. })),
+        "Tampered desc_M must fail verification"
+    );
+
+    // 11. Poseidon spec mismatch → specific error, not a silent `false`
+    let mut foreign_proof = proof.clone();
+    foreign_proof.poseidon_spec_id += halo2_proofs::halo2curves::bn256::Fr::one();
+    let mismatch = prover.verify(
+        &foreign_proof,
+        witness.h_pub,
+        witness.h_sig,
+        witness.desc_m,
+        witness.m_hash,
+    );
+    assert!(matches!(mismatch, Err(ToposhieldError::PoseidonSpecMismatch { .. })));
+
+    Ok(())
+}
+
+#[test]
+fn test_prove_with_deterministic_rng() -> Result<(), Box<dyn std::error::Error>> {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    let prover = TopoShieldProver::new()?;
+    let witness = Witness::new(b"Seeded RNG proof", b"seeded_rng_seed");
+    let mut rng = ChaCha20Rng::from_seed([7u8; 32]);
+    let proof = prover.prove_with_rng(witness.clone(), &mut rng)?;
+    let is_valid = prover.verify(
+        &proof,
+        witness.h_pub,
+        witness.h_sig,
+        witness.desc_m,
+        witness.m_hash,
+    )?;
+    assert!(is_valid, "Proof blinded with a seeded CSPRNG must verify");
+    Ok(())
+}
+
+#[test]
+fn test_prove_deterministic_is_reproducible() -> Result<(), Box<dyn std::error::Error>> {
+    let prover = TopoShieldProver::new()?;
+    let witness = Witness::new(b"Deterministic proof", b"deterministic_seed");
+    let first = prover.prove_deterministic(witness.clone(), [11u8; 32])?;
+    let second = prover.prove_deterministic(witness.clone(), [11u8; 32])?;
+    assert_eq!(first.bytes, second.bytes, "Same seed must give identical proof bytes");
+
+    for proof in [&first, &second] {
+        assert!(prover.verify(proof, witness.h_pub, witness.h_sig, witness.desc_m, witness.m_hash)?);
+    }
+
+    let other = prover.prove_deterministic(witness, [12u8; 32])?;
+    assert_ne!(first.bytes, other.bytes);
+    Ok(())
+}
+
+/// Pinned proof for a fixed message, seed, blinding seed and seeded SRS. Any change to
+/// the witness derivation, circuit, SRS convention or transcript changes the bytes.
+/// A missing fixture is a failure; (re)generate it deliberately with
+/// TOPOSHIELD_UPDATE_GOLDEN=1 and commit it.
+#[test]
+fn test_golden_proof() -> Result<(), Box<dyn std::error::Error>> {
+    use halo2_proofs::halo2curves::bn256::Bn256;
+    use halo2_proofs::poly::kzg::commitment::ParamsKZG;
+    use rand::SeedableRng;
+    use toposhield::prover::SRS_K;
+
+    const GOLDEN_PATH: &str = "tests/fixtures/golden_proof.json";
+    const MESSAGE: &[u8] = b"TopoShield golden proof";
+    const SEED: &[u8] = b"golden_seed";
+    const RNG_SEED: [u8; 32] = [0x42; 32];
+    const SRS_SEED: [u8; 32] = [0x5a; 32];
+
+    // Seeded SRS so the proof does not depend on the local params/kzg.srs
+    let params = ParamsKZG::<Bn256>::setup(SRS_K, rand_chacha::ChaCha20Rng::from_seed(SRS_SEED));
+    let prover = TopoShieldProver::from_params(params)?;
+    let witness = Witness::new(MESSAGE, SEED);
+    let proof = prover.prove_deterministic(witness.clone(), RNG_SEED)?;
+    assert!(prover.verify(&proof, witness.h_pub, witness.h_sig, witness.desc_m, witness.m_hash)?);
+
+    let golden = serde_json::json!({
+        "message": hex::encode(MESSAGE),
+        "seed": hex::encode(SEED),
+        "rng_seed": hex::encode(RNG_SEED),
+        "srs_seed": hex::encode(SRS_SEED),
+        "h_pub": witness.h_pub,
+        "h_sig": witness.h_sig,
+        "desc_m": witness.desc_m,
+        "m_hash": witness.m_hash,
+        "poseidon_spec_id": proof.poseidon_spec_id,
+        "proof": hex::encode(&proof.bytes),
+    });
+
+    let path = std::path::Path::new(GOLDEN_PATH);
+    if std::env::var("TOPOSHIELD_UPDATE_GOLDEN").as_deref() == Ok("1") {
+        std::fs::create_dir_all(path.parent().expect("fixture directory"))?;
+        std::fs::write(path, serde_json::to_string_pretty(&golden)? + "\n")?;
+        eprintln!("⚠️  Wrote {}; review and commit it", GOLDEN_PATH);
+        return Ok(());
+    }
+
+    let pinned_bytes = std::fs::read(path).unwrap_or_else(|e| {
+        panic!(
+            "{} is missing ({}); generate it with TOPOSHIELD_UPDATE_GOLDEN=1 and commit it",
+            GOLDEN_PATH, e
+        )
+    });
+    let pinned: serde_json::Value = serde_json::from_slice(&pinned_bytes)?;
+    assert_eq!(
+        pinned, golden,
+        "golden proof changed; if intentional, rerun with TOPOSHIELD_UPDATE_GOLDEN=1 and commit {}",
+        GOLDEN_PATH
+    );
+    Ok(())
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn test_mmap_params_match_buffered() -> Result<(), Box<dyn std::error::Error>> {
+    use std::path::Path;
+    use toposhield::prover::{load_params, load_params_buffered};
+
+    let path = Path::new("params/kzg.srs");
+    let mapped = load_params(path)?;
+    let buffered = load_params_buffered(path)?;
+    let (mut mapped_bytes, mut buffered_bytes) = (Vec::new(), Vec::new());
+    mapped.write(&mut mapped_bytes)?;
+    buffered.write(&mut buffered_bytes)?;
+    assert_eq!(mapped_bytes, buffered_bytes, "mmap and buffered loaders must agree");
+
+    // A prover built on the mmap path still proves and verifies
+    let prover = TopoShieldProver::new()?;
+    let witness = Witness::new(b"mmap params", b"mmap_seed");
+    let proof = prover.prove(witness.clone())?;
+    assert!(prover.verify(&proof, witness.h_pub, witness.h_sig, witness.desc_m, witness.m_hash)?);
+    Ok(())
+}
+
+#[test]
+fn test_estimate_srs_memory_matches_params() -> Result<(), Box<dyn std::error::Error>> {
+    use halo2_proofs::SerdeFormat;
+    use std::path::Path;
+    use toposhield::prover::{estimate_srs_memory, load_params, SRS_K};
+
+    // Raw serialization stores every point uncompressed, as it is held in memory
+    let params = load_params(Path::new("params/kzg.srs"))?;
+    let mut raw = Vec::new();
+    params.write_custom(&mut raw, SerdeFormat::RawBytesUnchecked)?;
+    let estimate = estimate_srs_memory(SRS_K);
+    let tolerance = estimate / 100;
+    assert!(raw.len().abs_diff(estimate) <= tolerance, "estimate {} vs raw {}", estimate, raw.len());
+    Ok(())
+}
+
+/// desc_M per component against the circuit (src/poseidon_widths.rs): the preimage
+/// constants, then the hash itself via build/test/desc_m_poseidon, which runs the
+/// circuit's `PoseidonEx(14, 4)` alone
+#[test]
+fn test_desc_m_matches_circuit_poseidon() -> Result<(), Box<dyn std::error::Error>> {
+    use ff::PrimeField;
+    use halo2_circom::circuit::{CircomCircuit, CircomConfig};
+    use halo2_proofs::halo2curves::bn256::{Bn256, Fr};
+    use toposhield::manifold::{HyperbolicManifold, DOMAIN_DESC};
+
+    let manifold = HyperbolicManifold::new();
+    let rust_desc_m = manifold.descriptor();
+
+    // Traces hardcoded as desc.in[4..14] in holonomy_path_enhanced.circom
+    let circuit_traces = [3u64, 4, 37, 10, 4, 5, 86, 18, 7, 18].map(Fr::from);
+    let rust_traces = manifold.generator_traces();
+    assert_eq!(circuit_traces, rust_traces, "circuit traces disagree with GENERATOR_ENTRIES");
+
+    // The circuit's Poseidon over the Rust preimage, outputs at wires 1..5
+    let config = CircomConfig::<Bn256>::new(
+        "build/test/desc_m_poseidon.r1cs",
+        "build/test/desc_m_poseidon.wasm",
+    )?;
+    let chi = if manifold.chi < 0 {
+        -Fr::from(manifold.chi.unsigned_abs() as u64)
+    } else {
+        Fr::from(manifold.chi as u64)
+    };
+    let header = [Fr::from(DOMAIN_DESC), Fr::from(manifold.genus as u64), chi, Fr::from(manifold.p_inv)];
+    let preimage: Vec<Fr> = header.into_iter().chain(rust_traces).collect();
+    let fr_to_hex = |f: &Fr| format!("0x{}", hex::encode(f.to_repr()));
+    let mut input = std::collections::BTreeMap::new();
+    input.insert("in".to_string(), serde_json::json!(preimage.iter().map(fr_to_hex).collect::<Vec<_>>()));
+    let wires = CircomCircuit::construct_witness_from_map(&config.r1cs, &mut input, config.aux_offset)?;
+    let circuit_desc_m = &wires[1..5];
+
+    assert_eq!(circuit_desc_m, rust_desc_m.as_slice(), "desc_M disagrees with the circuit's Poseidon");
+    Ok(())
+}
+
+#[test]
+fn test_circuit_witness_conformance() -> Result<(), Box<dyn std::error::Error>> {
+    use halo2_circom::circuit::{CircomCircuit, CircomConfig};
+    use halo2_proofs::{dev::MockProver, halo2curves::bn256::Bn256};
+    use std::collections::HashMap;
+
+    let witness = Witness::new(b"Circuit conformance", b"conformance_seed");
+
+    // Run the Rust witness through the unoptimized build (see `make compile-circuit`),
+    // where H_pub, H_sig and desc_M as computed by the circuit keep their own wires
+    let unoptimized = CircomConfig::<Bn256>::new(
+        "build/test/o0/holonomy_path_enhanced.r1cs",
+        "build/test/o0/holonomy_path_enhanced.wasm",
     )?;
-    assert!(!is_invalid_desc, "Tampered desc_M must fail verification");
+    let wires = CircomCircuit::construct_witness_from_map(
+        &unoptimized.r1cs,
+        &mut witness.to_circom_input()?,
+        unoptimized.aux_offset,
+    )?;
+
+    // circom symbol table lines: label index, wire index (-1 if none), component, name
+    let symbols = std::fs::read_to_string("build/test/o0/holonomy_path_enhanced.sym")?;
+    let wire_of: HashMap<&str, usize> = symbols
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(4, ',');
+            let wire = fields.nth(1)?.parse::<i64>().ok()?;
+            let name = fields.nth(1)?;
+            Some((name, usize::try_from(wire).ok()?))
+        })
+        .collect();
 
+    for (output, expected) in [
+        ("main.pubPath.result", witness.h_pub),
+        ("main.sigPath.result", witness.h_sig),
+        ("main.desc.out", witness.desc_m),
+    ] {
+        for (i, value) in expected.iter().enumerate() {
+            let name = format!("{}[{}]", output, i);
+            let wire = *wire_of.get(name.as_str()).ok_or_else(|| format!("{} has no wire", name))?;
+            assert_eq!(&wires[wire], value, "circuit {} disagrees with the Rust witness", name);
+        }
+    }
+
+    // The shipped (optimized) circuit is satisfied by the same witness
+    let config = CircomConfig::<Bn256>::new(
+        "build/holonomy_path_enhanced.r1cs",
+        "build/holonomy_path_enhanced.wasm",
+    )?;
+    let wires = CircomCircuit::construct_witness_from_map(
+        &config.r1cs,
+        &mut witness.to_circom_input()?,
+        config.aux_offset,
+    )?;
+    let public: Vec<_> = witness
+        .h_pub
+        .iter()
+        .chain(witness.h_sig.iter())
+        .chain(witness.desc_m.iter())
+        .chain(witness.m_hash.iter())
+        .copied()
+        .collect();
+    let circuit = CircomCircuit {
+        r1cs: config.r1cs.clone(),
+        witness: Some(wires),
+        wire_mapping: None,
+        aux_offset: config.aux_offset,
+    };
+    let mock = MockProver::run(17, &circuit, vec![public])?;
+    assert_eq!(mock.verify(), Ok(()));
+    Ok(())
+}
+
+#[test]
+fn test_circuit_rejects_out_of_range_index() -> Result<(), Box<dyn std::error::Error>> {
+    use halo2_circom::circuit::{CircomCircuit, CircomConfig};
+    use halo2_proofs::{dev::MockProver, halo2curves::bn256::Bn256};
+
+    // Index 20 falls through to the identity in GeneratorMatrix, so with H_pub and H_sig
+    // recomputed without that step only the circuit's range check can reject the path
+    let manifold = HyperbolicManifold::new();
+    let mut forged = Witness::new(b"Out of range index", b"range_seed");
+    let mut gamma = forged.gamma.to_vec();
+    forged.h_pub = manifold.holonomy(&gamma[1..]);
+    forged.h_sig = manifold.holonomy_extend(forged.h_pub, &forged.delta);
+    gamma[0] = 20;
+
+    let mut witness_map = forged.to_circom_input()?;
+    witness_map.insert("gamma".to_string(), serde_json::json!(gamma));
+
+    let config = CircomConfig::<Bn256>::new(
+        "build/holonomy_path_enhanced.r1cs",
+        "build/holonomy_path_enhanced.wasm",
+    )?;
+    let satisfied = match CircomCircuit::construct_witness_from_map(&config.r1cs, &mut witness_map, config.aux_offset) {
+        // The witness calculator already trips on the failed constraint
+        Err(_) => false,
+        Ok(wires) => {
+            let public: Vec<_> = forged
+                .h_pub
+                .iter()
+                .chain(forged.h_sig.iter())
+                .chain(forged.desc_m.iter())
+                .chain(forged.m_hash.iter())
+                .copied()
+                .collect();
+            let circuit = CircomCircuit {
+                r1cs: config.r1cs.clone(),
+                witness: Some(wires),
+                wire_mapping: None,
+                aux_offset: config.aux_offset,
+            };
+            MockProver::run(17, &circuit, vec![public])?.verify().is_ok()
+        }
+    };
+    assert!(!satisfied, "a path index of 20 satisfied the circuit");
+    Ok(())
+}
+
+#[test]
+fn test_prove_timed_metrics() -> Result<(), Box<dyn std::error::Error>> {
+    let prover = TopoShieldProver::new()?;
+    let witness = Witness::new(b"Timed proof", b"timed_seed");
+    let (proof, metrics) = prover.prove_timed(witness.clone())?;
+    assert!(prover.verify(&proof, witness.h_pub, witness.h_sig, witness.desc_m, witness.m_hash)?);
+
+    assert!(!metrics.witness_map.is_zero());
+    if toposhield::prover::ProverOptions::default().run_mock_prover {
+        assert!(!metrics.mock_prover.is_zero());
+    }
+    assert!(!metrics.create_proof.is_zero());
+    let phases = metrics.witness_map + metrics.mock_prover + metrics.create_proof;
+    assert!(phases <= metrics.total, "phases must not exceed wall-clock total");
+    assert!(phases * 10 >= metrics.total * 9, "phases should account for ~all of the total");
+    Ok(())
+}
+
+#[test]
+fn test_instance_layout_validated_against_circuit() -> Result<(), Box<dyn std::error::Error>> {
+    use toposhield::instance::{InstanceComponent, InstanceLayout};
+
+    let prover = TopoShieldProver::new()?;
+    assert_eq!(prover.instance_layout(), &InstanceLayout::default());
+
+    let short = InstanceLayout::new(vec![
+        InstanceComponent::HPub,
+        InstanceComponent::HSig,
+        InstanceComponent::DescM,
+    ]);
+    let result = prover.with_instance_layout(short);
+    assert!(matches!(
+        result,
+        Err(ToposhieldError::InstanceLayoutMismatch { expected: 16, got: 12 })
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_descriptor_compat_registry() -> Result<(), Box<dyn std::error::Error>> {
+    use halo2_proofs::halo2curves::bn256::Fr;
+    use toposhield::descriptor::DescriptorCompat;
+
+    let witness = Witness::new(b"Descriptor compat", b"compat_seed");
+    let legacy = [Fr::from(1), Fr::from(2), Fr::from(3), Fr::from(4)];
+
+    // Current + legacy registered: the current-version proof verifies
+    let prover = TopoShieldProver::new()?
+        .with_descriptor_compat(DescriptorCompat::default().with_version(0, legacy));
+    let proof = prover.prove(witness.clone())?;
+    assert!(prover.verify(&proof, witness.h_pub, witness.h_sig, witness.desc_m, witness.m_hash)?);
+
+    // Only the legacy version registered: the current descriptor is rejected
+    let legacy_only = prover.with_descriptor_compat(DescriptorCompat::empty().with_version(0, legacy));
+    let result = legacy_only.verify(&proof, witness.h_pub, witness.h_sig, witness.desc_m, witness.m_hash);
+    assert!(matches!(result, Err(ToposhieldError::UnsupportedDescriptor { .. })));
+    Ok(())
+}
+
+#[test]
+fn test_accepted_manifolds_pin() -> Result<(), Box<dyn std::error::Error>> {
+    use toposhield::descriptor::DescriptorCompat;
+
+    let witness = Witness::new(b"Pinned manifolds", b"pinned_seed");
+    let production = HyperbolicManifold::new().descriptor();
+    let mut staging = HyperbolicManifold::new();
+    staging.p_inv = 54321;
+    let mut rogue = HyperbolicManifold::new();
+    rogue.p_inv = 99999;
+    let (staging, rogue) = (staging.descriptor(), rogue.descriptor());
+
+    // Every descriptor is a registered version; only the pin tells them apart
+    let prover = TopoShieldProver::new()?
+        .with_descriptor_compat(DescriptorCompat::default().with_version(90, staging).with_version(91, rogue))
+        .with_accepted_manifolds(vec![production, staging]);
+    let proof = prover.prove(witness.clone())?;
+    assert!(prover.verify(&proof, witness.h_pub, witness.h_sig, production, witness.m_hash)?);
+    // Staging is accepted, so the proof only fails the pairing check
+    assert!(!prover.verify(&proof, witness.h_pub, witness.h_sig, staging, witness.m_hash)?);
+    assert!(matches!(
+        prover.verify(&proof, witness.h_pub, witness.h_sig, rogue, witness.m_hash),
+        Err(ToposhieldError::UnacceptedManifold { desc_m }) if desc_m == rogue
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_diagnose_reports_stages() -> Result<(), Box<dyn std::error::Error>> {
+    use toposhield::diagnostics::{DiagnosticStage, StageOutcome};
+
+    let prover = TopoShieldProver::new()?;
+    let witness = Witness::new(b"Diagnose", b"diagnose_seed");
+    let report = prover.diagnose(&witness);
+    assert!(report.all_passed(), "valid witness must pass every stage: {:?}", report);
+    assert_eq!(report.stages.len(), 7);
+
+    let mut corrupted = witness.clone();
+    corrupted.h_pub[0] += halo2_proofs::halo2curves::bn256::Fr::one();
+    let report = prover.diagnose(&corrupted);
+    assert_eq!(report.first_failure().map(|(stage, _)| stage), Some(DiagnosticStage::Structural));
+    assert_eq!(report.outcome(DiagnosticStage::MockProver), Some(&StageOutcome::Skipped));
+    Ok(())
+}
+
+#[test]
+fn test_prove_rejects_foreign_descriptor() -> Result<(), Box<dyn std::error::Error>> {
+    let prover = TopoShieldProver::new()?;
+    let mut witness = Witness::new(b"Foreign manifold", b"descriptor_seed");
+    let expected = witness.desc_m;
+    witness.desc_m[0] += halo2_proofs::halo2curves::bn256::Fr::one();
+    let found = witness.desc_m;
+
+    let err = prover.prove(witness).expect_err("foreign descriptor must not prove");
+    match err.downcast_ref::<ToposhieldError>() {
+        Some(ToposhieldError::DescriptorMismatch { expected: e, found: f }) => {
+            assert_eq!((*e, *f), (expected, found));
+        }
+        other => panic!("expected DescriptorMismatch, got {:?}", other),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_prove_rejects_non_sl2_holonomy() -> Result<(), Box<dyn std::error::Error>> {
+    let prover = TopoShieldProver::new()?;
+    let mut witness = Witness::new(b"Bad determinant", b"determinant_seed");
+    // det becomes 1 + h_pub[3]
+    witness.h_pub[0] += halo2_proofs::halo2curves::bn256::Fr::one();
+
+    let err = prover.prove(witness.clone()).expect_err("det ≠ 1 must fail before proving");
+    assert!(matches!(
+        err.downcast_ref::<ToposhieldError>(),
+        Some(ToposhieldError::NonSl2Holonomy { component: "h_pub" })
+    ));
+    // prove_batch builds its circuits the same way
+    let err = prover.prove_batch(&[witness]).expect_err("det ≠ 1 must fail before proving");
+    assert!(matches!(
+        err.downcast_ref::<ToposhieldError>(),
+        Some(ToposhieldError::NonSl2Holonomy { component: "h_pub" })
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_verify_many_preserves_order() -> Result<(), Box<dyn std::error::Error>> {
+    use toposhield::prover::VerifyRequest;
+
+    let witness = Witness::new(b"Verify many", b"verify_many_seed");
+    let prover = TopoShieldProver::new()?.with_batch_chunk_size(2);
+    let proof = prover.prove(witness.clone())?;
+    let valid = VerifyRequest {
+        proof: &proof,
+        h_pub: witness.h_pub,
+        h_sig: witness.h_sig,
+        desc_m: witness.desc_m,
+        m_hash: witness.m_hash,
+    };
+    let mut wrong_message = valid;
+    wrong_message.m_hash[0] += halo2_proofs::halo2curves::bn256::Fr::one();
+
+    let requests = [valid, wrong_message, valid, valid, wrong_message];
+    let results: Vec<bool> = prover.verify_many(&requests).into_iter().collect::<Result<_, _>>()?;
+    assert_eq!(results, vec![true, false, true, true, false]);
+    Ok(())
+}
+
+#[test]
+fn test_verify_with_commitment() -> Result<(), Box<dyn std::error::Error>> {
+    let witness = Witness::new(b"Committed proof", b"commitment_seed");
+    let prover = TopoShieldProver::new()?;
+    let proof = prover.prove(witness.clone())?;
+    let commitment = proof.transcript_hash();
+    let check = |proof| {
+        prover.verify_with_commitment(proof, commitment, witness.h_pub, witness.h_sig, witness.desc_m, witness.m_hash)
+    };
+    assert!(check(&proof)?);
+
+    // A fresh proof of the same statement verifies, but is not the committed one
+    let other = prover.prove(witness.clone())?;
+    assert!(prover.verify(&other, witness.h_pub, witness.h_sig, witness.desc_m, witness.m_hash)?);
+    assert_ne!(other.transcript_hash(), commitment);
+    assert!(!check(&other)?);
+
+    let mut tampered = proof.clone();
+    tampered.bytes[0] ^= 1;
+    assert!(!check(&tampered)?);
+    Ok(())
+}
+
+#[test]
+fn test_instance_count_mismatch() -> Result<(), Box<dyn std::error::Error>> {
+    let witness = Witness::new(b"Instance count", b"instance_count_seed");
+    let prover = TopoShieldProver::new()?;
+    let proof = prover.prove(witness.clone())?;
+    let mut instance: Vec<_> = [witness.h_pub, witness.h_sig, witness.desc_m, witness.m_hash].concat();
+    assert!(prover.verify_instance(&proof, &instance)?);
+
+    instance.push(witness.m_hash[0]);
+    assert!(matches!(
+        prover.verify_instance(&proof, &instance),
+        Err(ToposhieldError::InstanceCountMismatch { expected: 16, got: 17 })
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_minimal_params_verification_matches_full() -> Result<(), Box<dyn std::error::Error>> {
+    use toposhield::prover::{verify_minimal, MinimalVerifierParams};
+
+    let witness = Witness::new(b"Minimal verifier params", b"minimal_params_seed");
+    let prover = TopoShieldProver::new()?;
+    let proof = prover.prove(witness.clone())?;
+    let instance: Vec<_> = [witness.h_pub, witness.h_sig, witness.desc_m, witness.m_hash].concat();
+
+    let bytes = prover.minimal_verifier_params().to_bytes();
+    assert_eq!(bytes.len(), MinimalVerifierParams::BYTES);
+    let minimal = MinimalVerifierParams::from_bytes(&bytes)?;
+    assert_eq!(minimal, prover.minimal_verifier_params());
+    assert!(MinimalVerifierParams::from_bytes(&bytes[1..]).is_err());
+
+    let vk = prover.verifying_key();
+    assert!(prover.verify_instance(&proof, &instance)?);
+    assert!(verify_minimal(vk, &minimal, &proof, &instance)?);
+
+    let mut tampered = proof.clone();
+    let mid = tampered.bytes.len() / 2;
+    tampered.bytes[mid] ^= 1;
+    assert_eq!(
+        verify_minimal(vk, &minimal, &tampered, &instance)?,
+        prover.verify_instance(&tampered, &instance)?
+    );
+    assert!(!verify_minimal(vk, &minimal, &tampered, &instance)?);
+
+    let mut wrong_instance = instance.clone();
+    wrong_instance[12] += halo2_proofs::halo2curves::bn256::Fr::one();
+    assert!(!prover.verify_instance(&proof, &wrong_instance)?);
+    assert!(!verify_minimal(vk, &minimal, &proof, &wrong_instance)?);
+    Ok(())
+}
+
+#[test]
+fn test_verifier_retains_fixed_size_params() -> Result<(), Box<dyn std::error::Error>> {
+    use halo2_proofs::{
+        halo2curves::bn256::{G1Affine, G2Affine},
+        SerdeFormat,
+    };
+    use toposhield::instance::PublicInputs;
+    use toposhield::prover::{estimate_srs_memory, MinimalVerifierParams};
+
+    let witness = Witness::new(b"Constant-memory verifier", b"constant_memory_seed");
+    let prover = TopoShieldProver::new()?;
+    let proof = prover.prove(witness.clone())?;
+    let inputs = PublicInputs::from(&witness);
+
+    let verifier = prover.verifier();
+    assert!(verifier.verify(&proof, &inputs)?);
+    let mut wrong = inputs;
+    wrong.m_hash = Witness::hash_to_4fr(b"another message");
+    assert!(!verifier.verify(&proof, &wrong)?);
+
+    // Static-size check, not a runtime measurement: no degree-20 SRS or VK is built
+    // and no allocation is traced. It pins what the verifier retains: the VK plus one
+    // G1 and two G2 points, with no term in 2^k, against the 2·2^k points of the
+    // full SRS it replaces.
+    let vk_bytes = prover.verifying_key().to_bytes(SerdeFormat::RawBytes).len();
+    let points = std::mem::size_of::<G1Affine>() + 2 * std::mem::size_of::<G2Affine>();
+    assert_eq!(verifier.retained_size(), vk_bytes + points);
+    assert_eq!(prover.minimal_verifier_params().to_bytes().len(), MinimalVerifierParams::BYTES);
+    assert!(verifier.retained_size() < estimate_srs_memory(17) / 16);
+    Ok(())
+}
+
+#[test]
+fn test_proof_bundle_roundtrip_and_verify() -> Result<(), Box<dyn std::error::Error>> {
+    use toposhield::instance::PublicInputs;
+    use toposhield::prover::Proof;
+
+    let witness = Witness::new(b"Proof bundle", b"proof_bundle_seed");
+    let prover = TopoShieldProver::new()?;
+    let proof = prover.prove(witness.clone())?;
+    let inputs = PublicInputs::from(&witness);
+
+    let json = proof.to_bundle_json(&inputs, &prover.setup_fingerprint());
+    let bundle = Proof::from_bundle_json(&json)?;
+    assert_eq!(bundle.proof, proof);
+    assert_eq!(bundle.inputs, inputs);
+    assert_eq!(bundle.descriptor_version, toposhield::manifold::DESCRIPTOR_VERSION);
+    assert_eq!(bundle.proof.to_bundle_json(&bundle.inputs, &bundle.setup_fingerprint), json);
+
+    // A verifier needs nothing but the bundle text
+    assert!(prover.verify_bundle(&bundle)?);
+
+    let mut wrong_message = bundle.clone();
+    wrong_message.inputs.m_hash = Witness::hash_to_4fr(b"another message");
+    assert!(!prover.verify_bundle(&wrong_message)?);
+
+    let mut foreign_setup = bundle.clone();
+    foreign_setup.setup_fingerprint = "00".repeat(32);
+    assert!(matches!(prover.verify_bundle(&foreign_setup), Err(ToposhieldError::SetupMismatch { .. })));
+
+    assert!(matches!(
+        Proof::from_bundle_json(&json.replace("toposhield-proof-bundle/1", "toposhield-proof-bundle/0")),
+        Err(ToposhieldError::MalformedBundle { .. })
+    ));
+    assert!(matches!(Proof::from_bundle_json("{}"), Err(ToposhieldError::MalformedBundle { .. })));
+    Ok(())
+}
+
+#[test]
+fn test_custom_srs_path() -> Result<(), Box<dyn std::error::Error>> {
+    use toposhield::prover::write_params_hash;
+
+    let dir = std::env::temp_dir().join("toposhield_custom_srs");
+    std::fs::create_dir_all(&dir)?;
+    let srs = dir.join("shared.srs");
+    std::fs::copy("params/kzg.srs", &srs)?;
+    write_params_hash(&srs)?;
+
+    let prover = TopoShieldProver::with_srs_path(srs.clone(), None)?;
+    let witness = Witness::new(b"Custom SRS path", b"custom_srs_seed");
+    let proof = prover.prove(witness.clone())?;
+    assert!(prover.verify(&proof, witness.h_pub, witness.h_sig, witness.desc_m, witness.m_hash)?);
+
+    // An explicit hash file must exist and match
+    let missing = dir.join("missing.sha256");
+    assert!(TopoShieldProver::with_srs_path(srs.clone(), Some(missing)).is_err());
+    std::fs::write(dir.join("wrong.sha256"), format!("{}  shared.srs\n", "0".repeat(64)))?;
+    let err = TopoShieldProver::with_srs_path(srs, Some(dir.join("wrong.sha256"))).unwrap_err();
+    assert!(matches!(err.downcast_ref::<ToposhieldError>(), Some(ToposhieldError::SrsHashMismatch { .. })));
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_prover_from_embedded_artifacts() -> Result<(), Box<dyn std::error::Error>> {
+    let r1cs = std::fs::read("build/holonomy_path_enhanced.r1cs")?;
+    let wasm = std::fs::read("build/holonomy_path_enhanced.wasm")?;
+    let prover = TopoShieldProver::from_embedded(&r1cs, &wasm)?;
+
+    let witness = Witness::new(b"Embedded artifacts", b"embedded_seed");
+    let proof = prover.prove(witness.clone())?;
+    assert!(prover.verify(&proof, witness.h_pub, witness.h_sig, witness.desc_m, witness.m_hash)?);
+    assert!(TopoShieldProver::from_embedded(b"not an r1cs", &wasm).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_concurrent_proving_shared_prover() -> Result<(), Box<dyn std::error::Error>> {
+    use std::sync::Arc;
+    use std::thread;
+
+    let prover = Arc::new(TopoShieldProver::new()?);
+    let handles: Vec<_> = (0..4u8)
+        .map(|i| {
+            let prover = Arc::clone(&prover);
+            thread::spawn(move || {
+                let witness = Witness::new(&[b'T', i], b"concurrent_seed");
+                let proof = prover.prove(witness.clone()).map_err(|e| e.to_string())?;
+                prover
+                    .verify(&proof, witness.h_pub, witness.h_sig, witness.desc_m, witness.m_hash)
+                    .map_err(|e| e.to_string())
+            })
+        })
+        .collect();
+    for handle in handles {
+        assert_eq!(handle.join().expect("prover thread panicked"), Ok(true));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_epoch_bound_signature() -> Result<(), Box<dyn std::error::Error>> {
+    use toposhield::prover::PublicKey;
+
+    let prover = TopoShieldProver::new()?;
+    let witness = Witness::new_with_epoch(b"Epoch bound", b"epoch_bound_seed", 42);
+    let public_key = PublicKey::from(&witness);
+    let signature = prover.sign(witness)?;
+    assert!(prover.verify_message_at_epoch(&public_key, b"Epoch bound", 42, &signature));
+    assert!(!prover.verify_message_at_epoch(&public_key, b"Epoch bound", 43, &signature));
+    assert!(!prover.verify_message(&public_key, b"Epoch bound", &signature));
+    Ok(())
+}
+
+#[test]
+fn test_verify_from_reader_matches_verify() -> Result<(), Box<dyn std::error::Error>> {
+    use std::io::Cursor;
+    use toposhield::instance::PublicInputs;
+
+    let witness = Witness::new(b"Streamed proof", b"stream_seed");
+    let prover = TopoShieldProver::new()?;
+    let proof = prover.prove(witness.clone())?;
+    let inputs = PublicInputs::from(&witness);
+    let in_memory = prover.verify(&proof, witness.h_pub, witness.h_sig, witness.desc_m, witness.m_hash)?;
+    assert!(in_memory);
+    assert_eq!(prover.verify_from_reader(Cursor::new(proof.bytes.clone()), &inputs)?, in_memory);
+
+    // A reader that yields a few bytes at a time, as a socket might
+    let chunked = std::io::BufReader::with_capacity(7, proof.bytes.as_slice());
+    assert!(prover.verify_from_reader(chunked, &inputs)?);
+
+    let mut wrong = inputs;
+    wrong.m_hash[0] += halo2_proofs::halo2curves::bn256::Fr::one();
+    assert!(!prover.verify_from_reader(proof.bytes.as_slice(), &wrong)?);
+    let truncated = &proof.bytes[..proof.bytes.len() / 2];
+    assert!(!prover.verify_from_reader(truncated, &inputs)?);
+    Ok(())
+}
+
+#[test]
+fn test_malformed_public_inputs_rejected_early() -> Result<(), Box<dyn std::error::Error>> {
+    use halo2_proofs::halo2curves::bn256::Fr;
+
+    let witness = Witness::new(b"Malformed inputs", b"malformed_seed");
+    let prover = TopoShieldProver::new()?;
+    let proof = prover.prove(witness.clone())?;
+    let identity = [Fr::one(), Fr::zero(), Fr::zero(), Fr::one()];
+    let mut non_sl2 = witness.h_sig;
+    non_sl2[0] += Fr::one();
+    // det 0 but not the zero matrix
+    let singular = [Fr::one(), Fr::one(), Fr::one(), Fr::one()];
+
+    for (h_pub, h_sig, component, reason) in [
+        ([Fr::zero(); 4], witness.h_sig, "h_pub", "det ≠ 1"),
+        (singular, witness.h_sig, "h_pub", "det ≠ 1"),
+        (identity, witness.h_sig, "h_pub", "identity"),
+        (witness.h_pub, [Fr::zero(); 4], "h_sig", "det ≠ 1"),
+        (witness.h_pub, non_sl2, "h_sig", "det ≠ 1"),
+        (witness.h_pub, identity, "h_sig", "identity"),
+    ] {
+        let err = prover.verify(&proof, h_pub, h_sig, witness.desc_m, witness.m_hash).unwrap_err();
+        assert!(
+            matches!(err, ToposhieldError::InvalidPublicInputs { component: c, reason: r } if c == component && r == reason),
+            "{}",
+            err
+        );
+        // Every verify entry point runs the same check
+        let err = prover
+            .verify_batch(&proof, h_pub, witness.desc_m, &[(h_sig, witness.m_hash)])
+            .unwrap_err();
+        assert!(matches!(err, ToposhieldError::InvalidPublicInputs { component: c, .. } if c == component));
+    }
+
+    // Honest holonomies are in SL(2) and pass
+    assert!(prover.verify(&proof, witness.h_pub, witness.h_sig, witness.desc_m, witness.m_hash)?);
+    Ok(())
+}
+
+#[test]
+fn test_prover_state_roundtrip() -> Result<(), Box<dyn std::error::Error>> {
+    let state_dir = tempfile::TempDir::new()?;
+    let dir = state_dir.path().join("state");
+
+    let fresh = TopoShieldProver::new()?;
+    fresh.save_state(&dir)?;
+    let loaded = TopoShieldProver::load_state(&dir)?;
+
+    // Same keys: identical proofs for identical blinding, cross-verifying
+    let witness = Witness::new(b"Prover state", b"state_seed");
+    let from_fresh = fresh.prove_deterministic(witness.clone(), [11u8; 32])?;
+    let from_loaded = loaded.prove_deterministic(witness.clone(), [11u8; 32])?;
+    assert_eq!(from_fresh, from_loaded);
+    assert!(fresh.verify(&from_loaded, witness.h_pub, witness.h_sig, witness.desc_m, witness.m_hash)?);
+    assert!(loaded.verify(&from_fresh, witness.h_pub, witness.h_sig, witness.desc_m, witness.m_hash)?);
+
+    // A stale fingerprint triggers regeneration instead of loading old keys
+    let manifest_path = dir.join("state.json");
+    let mut manifest: serde_json::Value = serde_json::from_slice(&std::fs::read(&manifest_path)?)?;
+    manifest["fingerprint"] = serde_json::json!("stale");
+    std::fs::write(&manifest_path, serde_json::to_vec(&manifest)?)?;
+    let regenerated = TopoShieldProver::load_state(&dir)?;
+    let proof = regenerated.prove(witness.clone())?;
+    assert!(regenerated.verify(&proof, witness.h_pub, witness.h_sig, witness.desc_m, witness.m_hash)?);
+    let manifest: serde_json::Value = serde_json::from_slice(&std::fs::read(&manifest_path)?)?;
+    assert_ne!(manifest["fingerprint"], "stale");
+
+    // Current state with an altered proving key is corrupt: an error, not a rebuild
+    let pk_path = dir.join("pk.bin");
+    let pk_bytes = std::fs::read(&pk_path)?;
+    let mut altered = pk_bytes.clone();
+    altered[pk_bytes.len() / 2] ^= 1;
+    std::fs::write(&pk_path, &altered)?;
+    let is_corrupt = |result: Result<TopoShieldProver, Box<dyn std::error::Error>>| {
+        matches!(
+            result.err().and_then(|e| e.downcast::<ToposhieldError>().ok()).map(|e| *e),
+            Some(ToposhieldError::CorruptState { .. })
+        )
+    };
+    assert!(is_corrupt(TopoShieldProver::load_state(&dir)));
+    assert_eq!(std::fs::read(&pk_path)?, altered, "corrupt state must be left in place");
+    std::fs::write(&pk_path, &pk_bytes)?;
+
+    // So is a params file replaced after saving, and an unparsable manifest
+    std::fs::write(dir.join("kzg.srs"), b"not an SRS")?;
+    assert!(is_corrupt(TopoShieldProver::load_state(&dir)));
+    std::fs::write(&manifest_path, b"{ truncated")?;
+    assert!(is_corrupt(TopoShieldProver::load_state(&dir)));
+    Ok(())
+}
+
+#[test]
+fn test_prove_batch_shared_key() -> Result<(), Box<dyn std::error::Error>> {
+    let prover = TopoShieldProver::new()?;
+    let seed = b"batch_seed";
+    let witnesses: Vec<Witness> = [&b"Batch message 1"[..], b"Batch message 2", b"Batch message 3"]
+        .iter()
+        .map(|m| Witness::new(m, seed))
+        .collect();
+    let proof = prover.prove_batch(&witnesses)?;
+
+    let mut signatures: Vec<_> = witnesses.iter().map(|w| (w.h_sig, w.m_hash)).collect();
+    let (h_pub, desc_m) = (witnesses[0].h_pub, witnesses[0].desc_m);
+    assert!(prover.verify_batch(&proof, h_pub, desc_m, &signatures)?);
+
+    // Any altered message hash invalidates the whole batch
+    signatures[1].1[0] += halo2_proofs::halo2curves::bn256::Fr::one();
+    assert!(!prover.verify_batch(&proof, h_pub, desc_m, &signatures)?);
+
+    // Witnesses under different keys cannot be batched
+    let mixed = vec![witnesses[0].clone(), Witness::new(b"Batch message 1", b"other_seed")];
+    assert!(prover.prove_batch(&mixed).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_prove_without_mock_prover() -> Result<(), Box<dyn std::error::Error>> {
+    use toposhield::prover::ProverOptions;
+
+    let prover = TopoShieldProver::new()?;
+    let witness = Witness::new(b"No mock pass", b"no_mock_seed");
+    let options = ProverOptions { run_mock_prover: false };
+    let proof = prover.prove_with_options(witness.clone(), options)?;
+    assert!(prover.verify(&proof, witness.h_pub, witness.h_sig, witness.desc_m, witness.m_hash)?);
+
+    // Batches honour the same options
+    let witnesses = vec![witness.clone(), Witness::new(b"No mock pass 2", b"no_mock_seed")];
+    let batch = prover.prove_batch_with_options(&witnesses, options)?;
+    let signatures: Vec<_> = witnesses.iter().map(|w| (w.h_sig, w.m_hash)).collect();
+    assert!(prover.verify_batch(&batch, witness.h_pub, witness.desc_m, &signatures)?);
+    Ok(())
+}
+
+#[test]
+fn test_explicit_wire_mapping() -> Result<(), Box<dyn std::error::Error>> {
+    use toposhield::diagnostics::{DiagnosticStage, StageOutcome};
+
+    let short_prover = TopoShieldProver::new()?;
+    let dir = std::env::temp_dir();
+
+    // A mapping whose length doesn't cover the R1CS wires is rejected
+    let short = dir.join("toposhield_short_wire_map.json");
+    std::fs::write(&short, "[0, 1, 2]")?;
+    let result = short_prover.with_wire_mapping(&short);
+    assert!(matches!(
+        result.err().and_then(|e| e.downcast::<ToposhieldError>().ok()).map(|e| *e),
+        Some(ToposhieldError::WireMappingMismatch { witness_wires: 3, .. })
+    ));
+
+    // A non-identity mapping covering every wire is accepted, but it assigns the
+    // witness to the wrong wires of this circuit, so proving with it must fail
+    let prover = TopoShieldProver::new()?;
+    let witness = Witness::new(b"Wire mapping", b"wire_seed");
+    let (witness_vec, num_inputs) = {
+        use halo2_circom::circuit::{CircomCircuit, CircomConfig};
+        use halo2_proofs::halo2curves::bn256::Bn256;
+        let config = CircomConfig::<Bn256>::new(
+            "build/holonomy_path_enhanced.r1cs",
+            "build/holonomy_path_enhanced.wasm",
+        )?;
+        let mut map = witness.to_circom_input()?;
+        let witness_vec = CircomCircuit::construct_witness_from_map(&config.r1cs, &mut map, config.aux_offset)?;
+        (witness_vec, config.r1cs.num_inputs)
+    };
+    // Swap the last wire with a private wire holding a different value, so the
+    // permutation really changes the assignment
+    let wires = witness_vec.len();
+    let other = (num_inputs..wires - 1)
+        .rev()
+        .find(|&k| witness_vec[k] != witness_vec[wires - 1])
+        .expect("the circuit has private wires with distinct values");
+    let mut mapping: Vec<usize> = (0..wires).collect();
+    mapping.swap(other, wires - 1);
+    let full = dir.join("toposhield_full_wire_map.json");
+    std::fs::write(&full, serde_json::to_string(&mapping)?)?;
+    let permuted = prover.with_wire_mapping(&full)?;
+    assert!(
+        permuted.prove(witness.clone()).is_err(),
+        "a permuted wire mapping must not yield a proof for this circuit"
+    );
+    // The witness itself is fine: the identity mapping passes the mock check, the
+    // permuted one fails it
+    let identity = dir.join("toposhield_identity_wire_map.json");
+    std::fs::write(&identity, serde_json::to_string(&(0..wires).collect::<Vec<usize>>())?)?;
+    let report = TopoShieldProver::new()?.with_wire_mapping(&identity)?.diagnose(&witness);
+    assert_eq!(report.outcome(DiagnosticStage::MockProver), Some(&StageOutcome::Passed));
+    let report = permuted.diagnose(&witness);
+    assert_eq!(report.first_failure().map(|(stage, _)| stage), Some(DiagnosticStage::MockProver));
+    Ok(())
+}
+
+#[test]
+fn test_verify_detailed_outcomes() -> Result<(), Box<dyn std::error::Error>> {
+    use toposhield::prover::VerifyOutcome;
+
+    let prover = TopoShieldProver::new()?;
+    let witness = Witness::new(b"Detailed verification", b"detailed_seed");
+    let proof = prover.prove(witness.clone())?;
+    let (h_pub, h_sig, desc_m, m_hash) = (witness.h_pub, witness.h_sig, witness.desc_m, witness.m_hash);
+
+    assert_eq!(prover.verify_detailed(&proof, h_pub, h_sig, desc_m, m_hash)?, VerifyOutcome::Valid);
+
+    // h_pub and h_sig swapped: an ordering bug, not an invalid proof
+    assert_eq!(
+        prover.verify_detailed(&proof, h_sig, h_pub, desc_m, m_hash)?,
+        VerifyOutcome::InstanceMismatch
+    );
+    // verify itself makes the single check and just rejects it
+    assert!(!prover.verify(&proof, h_sig, h_pub, desc_m, m_hash)?);
+
+    // Wrong message hash: the pairing check fails under every ordering
+    let mut wrong_m_hash = m_hash;
+    wrong_m_hash[0] += halo2_proofs::halo2curves::bn256::Fr::one();
+    assert_eq!(
+        prover.verify_detailed(&proof, h_pub, h_sig, desc_m, wrong_m_hash)?,
+        VerifyOutcome::InvalidPairing
+    );
+
+    // Truncated transcript
+    let mut truncated = proof.clone();
+    truncated.bytes.truncate(proof.bytes.len() / 2);
+    assert_eq!(
+        prover.verify_detailed(&truncated, h_pub, h_sig, desc_m, m_hash)?,
+        VerifyOutcome::MalformedProof
+    );
+    assert!(!prover.verify(&truncated, h_pub, h_sig, desc_m, m_hash)?);
+    Ok(())
+}
+
+#[test]
+fn test_setup_kzg_json_output() -> Result<(), Box<dyn std::error::Error>> {
+    // Run in a scratch directory so the real params/kzg.srs is untouched
+    let dir = std::env::temp_dir().join("toposhield_setup_json");
+    std::fs::create_dir_all(&dir)?;
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_setup-kzg"))
+        .arg("--json")
+        .env("TOPOSHIELD_SETUP_SEED", "07".repeat(32))
+        .current_dir(&dir)
+        .output()?;
+    assert!(output.status.success());
+
+    let status: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(status["completed"], true);
+    assert_eq!(status["k"], 17);
+    assert_eq!(status["path"], "params/kzg.srs");
+    assert_eq!(status["seeded"], true);
+    assert_eq!(status["sha256"].as_str().map(str::len), Some(64));
+    Ok(())
+}
+
+#[test]
+fn test_srs_rehash_fixes_stale_hash() -> Result<(), Box<dyn std::error::Error>> {
+    use halo2_proofs::halo2curves::bn256::Bn256;
+    use halo2_proofs::poly::{commitment::Params, kzg::commitment::ParamsKZG};
+    use rand::SeedableRng;
+    use toposhield::prover::{load_and_verify_params, params_hash_path, write_params_hash};
+
+    let dir = std::env::temp_dir().join("toposhield_srs_rehash");
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join("kzg.srs");
+    let write_srs = |seed: u64| -> Result<(), Box<dyn std::error::Error>> {
+        let params = ParamsKZG::<Bn256>::setup(4, rand_chacha::ChaCha20Rng::seed_from_u64(seed));
+        let mut bytes = Vec::new();
+        params.write(&mut bytes)?;
+        std::fs::write(&path, bytes)?;
+        Ok(())
+    };
+
+    write_srs(1)?;
+    write_params_hash(&path)?;
+    assert!(load_and_verify_params(&path).is_ok());
+
+    // Regenerated SRS, stale hash file
+    write_srs(2)?;
+    let err = load_and_verify_params(&path).expect_err("stale hash must be detected");
+    assert!(matches!(
+        err.downcast_ref::<ToposhieldError>(),
+        Some(ToposhieldError::SrsHashMismatch { .. })
+    ));
+    assert!(err.to_string().contains("srs-rehash"));
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_srs-rehash"))
+        .arg(&path)
+        .arg("--yes")
+        .output()?;
+    assert!(output.status.success());
+    assert!(std::fs::read_to_string(params_hash_path(&path))?.ends_with("  kzg.srs\n"));
+    assert!(load_and_verify_params(&path).is_ok());
+    Ok(())
+}
+
+#[test]
+fn test_minimum_k_supports_keygen() -> Result<(), Box<dyn std::error::Error>> {
+    use halo2_circom::circuit::{CircomCircuit, CircomConfig};
+    use halo2_proofs::{halo2curves::bn256::Bn256, plonk::keygen_vk, poly::kzg::commitment::ParamsKZG};
+    use rand::SeedableRng;
+    use toposhield::prover::{minimum_k_for_circuit, SRS_K};
+
+    let config = CircomConfig::<Bn256>::new(
+        "build/holonomy_path_enhanced.r1cs",
+        "build/holonomy_path_enhanced.wasm",
+    )?;
+    let k = minimum_k_for_circuit(&config.r1cs);
+    assert!(k <= SRS_K);
+
+    let params = ParamsKZG::<Bn256>::setup(k, rand_chacha::ChaCha20Rng::seed_from_u64(k as u64));
+    let circuit = CircomCircuit {
+        r1cs: config.r1cs.clone(),
+        witness: Some(vec![]),
+        wire_mapping: config.r1cs.wire_mapping.clone(),
+        aux_offset: config.aux_offset,
+    };
+    keygen_vk(&params, &circuit)?;
+    Ok(())
+}
+
+#[test]
+fn test_small_srs_rejected_before_keygen() -> Result<(), Box<dyn std::error::Error>> {
+    use halo2_proofs::halo2curves::bn256::Bn256;
+    use halo2_proofs::poly::kzg::commitment::ParamsKZG;
+    use rand::SeedableRng;
+    use toposhield::prover::{required_srs_k, SRS_K};
+
+    let summary = TopoShieldProver::new()?.r1cs_summary();
+    let required = required_srs_k(summary.num_constraints, summary.num_public_inputs);
+    assert!(required > 10 && required <= SRS_K);
+
+    let small = ParamsKZG::<Bn256>::setup(10, rand_chacha::ChaCha20Rng::seed_from_u64(10));
+    let err = match TopoShieldProver::from_params(small) {
+        Ok(_) => panic!("k = 10 must be rejected"),
+        Err(err) => err,
+    };
+    assert!(matches!(
+        err.downcast_ref::<ToposhieldError>(),
+        Some(ToposhieldError::SrsTooSmall { required_k, available_k: 10 }) if *required_k == required
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_srs_wrong_generator_rejected() -> Result<(), Box<dyn std::error::Error>> {
+    use halo2_proofs::halo2curves::{bn256::G1Affine, group::prime::PrimeCurveAffine};
+    use halo2_proofs::poly::commitment::ParamsProver;
+    use std::path::Path;
+    use toposhield::prover::{load_params, verify_srs_compatibility};
+
+    // TopoShieldProver::new writes params/kzg.srs if it is missing
+    let _ = TopoShieldProver::new()?;
+    let params = load_params(Path::new("params/kzg.srs"))?;
+    assert!(verify_srs_compatibility(&params).is_ok());
+
+    // Same size and G2 elements, but the G1 powers start at 2·G
+    let mut g = params.get_g().to_vec();
+    g[0] = (G1Affine::generator() + G1Affine::generator()).into();
+    let tampered = params.from_parts(params.k(), g, None, params.g2(), params.s_g2());
+    assert!(matches!(
+        verify_srs_compatibility(&tampered),
+        Err(ToposhieldError::SrsWrongGenerator)
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_larger_srs_accepted() -> Result<(), Box<dyn std::error::Error>> {
+    use halo2_proofs::{halo2curves::bn256::Bn256, poly::kzg::commitment::ParamsKZG};
+    use rand::SeedableRng;
+    use toposhield::prover::{verify_srs_compatibility, SRS_K};
+
+    // Any k at or above the circuit's minimum is usable, not only the default SRS_K
+    let k = SRS_K + 1;
+    let params = ParamsKZG::<Bn256>::setup(k, rand_chacha::ChaCha20Rng::seed_from_u64(k as u64));
+    assert!(verify_srs_compatibility(&params).is_ok());
+
+    let prover = TopoShieldProver::from_params(params)?;
+    let witness = Witness::new(b"Larger SRS", b"larger_srs_seed");
+    let proof = prover.prove(witness.clone())?;
+    assert!(prover.verify(&proof, witness.h_pub, witness.h_sig, witness.desc_m, witness.m_hash)?);
+    Ok(())
+}
+
+#[test]
+fn test_missing_params_error_names_file() {
+    use std::path::Path;
+    use toposhield::prover::load_params;
+
+    let path = Path::new("params/does_not_exist.srs");
+    let err = load_params(path).expect_err("missing SRS must fail");
+    let toposhield_err = err.downcast_ref::<ToposhieldError>().expect("contextual error");
+    assert!(matches!(toposhield_err, ToposhieldError::Io { path: p, .. } if p == path));
+    assert!(err.to_string().contains("params/does_not_exist.srs"));
+}
+
+#[test]
+fn test_r1cs_summary() -> Result<(), Box<dyn std::error::Error>> {
+    let prover = TopoShieldProver::new()?;
+    let summary = prover.r1cs_summary();
+    // H_pub, H_sig, desc_M, m_hash: 4 field elements each
+    assert_eq!(summary.num_public_inputs, 16);
+    assert_eq!(summary.num_public_inputs, prover.instance_layout().width());
+    assert!(summary.num_constraints > 0);
+    assert_eq!(summary.num_variables, 1 + summary.num_public_inputs + summary.num_private_wires);
+
+    let json: serde_json::Value = serde_json::to_value(summary)?;
+    assert_eq!(json["num_public_inputs"], 16);
+    assert!(json.get("aux_offset").is_some());
+    Ok(())
+}
+
+#[test]
+fn test_verify_message_with_public_key() -> Result<(), Box<dyn std::error::Error>> {
+    use toposhield::prover::PublicKey;
+
+    let prover = TopoShieldProver::new()?;
+    let message = b"High-level verification";
+    let witness = Witness::new(message, b"public_key_seed");
+    let public_key = PublicKey::from(&witness);
+    assert_eq!(public_key, PublicKey::new(witness.h_pub));
+    let signature = prover.sign(witness)?;
+
+    assert!(prover.verify_message(&public_key, message, &signature));
+    assert!(!prover.verify_message(&public_key, b"Another message", &signature));
+
+    let other_key = PublicKey::from(&Witness::new(message, b"other_public_key_seed"));
+    assert!(!prover.verify_message(&other_key, message, &signature));
     Ok(())
 }
 
@@ -133,24 +1286,25 @@ fn test_enhanced_desc_m_consistency() {
     let w = Witness::new(b"Desc test", b"desc_seed");
     // Recompute expected desc_M manually
     use halo2_proofs::halo2curves::bn256::Fr;
-    use poseidon::{PoseidonHasher, Spec};
-    let mut hasher = PoseidonHasher::<Fr, _, 4, 1>::new(Spec::new());
-    hasher.update(&[
-        Fr::from(5u64),        // genus
-        -Fr::from(8u64),       // χ
-        Fr::from(12345u64),    // p_inv
-        Fr::from(3u64),        // tr(a1) = 2+1
-        Fr::from(4u64),        // tr(b1) = 3+1
-        Fr::from(6u64),        // tr(a2) = 5+1
-        Fr::from(9u64),        // tr(b2) = 7+2
-        Fr::from(14u64),       // tr(a3) = 11+3
-        Fr::from(16u64),       // tr(b3) = 13+3
-        Fr::from(21u64),       // tr(a4) = 17+4
-        Fr::from(24u64),       // tr(b4) = 19+5
-        Fr::from(29u64),       // tr(a5) = 23+6
-        Fr::from(26u64),       // tr(b5) = 21+5
-    ]);
-    let expected = hasher.squeeze();
-    let expected_desc = [expected[0], expected[1], expected[2], expected[3]];
-    assert_eq!(w.desc_m, expected_desc, "desc_M must include generator traces");
+    use toposhield::circom_poseidon::CircomPoseidon;
+    let expected = CircomPoseidon::new(14).hash_ex(
+        &[
+            Fr::from(1u64),        // DOMAIN_DESC
+            Fr::from(5u64),        // genus
+            -Fr::from(8u64),       // χ
+            Fr::from(12345u64),    // p_inv
+            Fr::from(3u64),        // tr(a1)
+            Fr::from(4u64),        // tr(b1)
+            Fr::from(37u64),       // tr(a2)
+            Fr::from(10u64),       // tr(b2)
+            Fr::from(4u64),        // tr(a3)
+            Fr::from(5u64),        // tr(b3)
+            Fr::from(86u64),       // tr(a4)
+            Fr::from(18u64),       // tr(b4)
+            Fr::from(7u64),        // tr(a5)
+            Fr::from(18u64),       // tr(b5)
+        ],
+        4,
+    );
+    assert_eq!(w.desc_m.to_vec(), expected, "desc_M must include generator traces");
 }